@@ -0,0 +1,1015 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    token, Attribute, Expr, FnArg, ForeignItemFn, GenericArgument, Ident, LitStr, Pat,
+    PathArguments, ReturnType, Token, Type,
+};
+
+use crate::{into_args, is_bytes_ty, is_unit_ty};
+
+/*
+
+    #[endpoint(
+        namespace = library,
+        method = GET,
+        path = "/books/:book_id",
+        ok = OK,
+        none = NOT_FOUND, // optional, `get_book_by_id_opt`를 만듦
+        error(NOT_FOUND => NotFoundBook, "Not found book"),
+        // 응답 body를 json으로 읽어서 variant에 넣음, `map`이 있으면 `map`으로 바꿈
+        error(BAD_REQUEST => InvalidBook(String), "Invalid book: {0}", map = |buf| ...),
+    )]
+    pub async fn get_book_by_id(#[path] book_id: u32) -> model::Book;
+
+*/
+
+pub(crate) struct EndpointArgs {
+    namespace: Ident,
+    method: Ident,
+    path: LitStr,
+    ok: Ident,
+    errors: Vec<ErrorArm>,
+    map: Option<Expr>,
+    /// `map`을 쓸 때 실제 응답 body의 타입
+    response: Option<Type>,
+    /// `<fn>_opt`에서 `Ok(None)`으로 취급할 status
+    none: Option<Ident>,
+    /// `page`, `per_page` 인자로 `Builder::paginate`를 만듦
+    paginate: bool,
+}
+
+struct ErrorArm {
+    status: Ident,
+    variant: Ident,
+    /// `Variant(T)`의 `T`, 응답 body로 만듦
+    ty: Option<Type>,
+    message: LitStr,
+    /// `fn(Bytes) -> Result<T, BaseError>`, 없으면 json으로 읽음
+    map: Option<Expr>,
+}
+
+impl Parse for ErrorArm {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let status = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let variant = input.parse()?;
+
+        let ty = if input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            Some(content.parse()?)
+        } else {
+            None
+        };
+
+        input.parse::<Token![,]>()?;
+        let message = input.parse()?;
+
+        let map = if input.is_empty() {
+            None
+        } else {
+            input.parse::<Token![,]>()?;
+
+            let key: Ident = input.parse()?;
+            if key != "map" {
+                return Err(syn::Error::new(key.span(), "expected `map = ..`"));
+            }
+            input.parse::<Token![=]>()?;
+
+            Some(input.parse::<Expr>()?)
+        };
+
+        if let (None, Some(map)) = (&ty, &map) {
+            let msg = "`map` needs a variant with a field like `Variant(T)`";
+            return Err(syn::Error::new(map.span(), msg));
+        }
+
+        Ok(Self {
+            status,
+            variant,
+            ty,
+            message,
+            map,
+        })
+    }
+}
+
+impl Parse for EndpointArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut namespace = None;
+        let mut method = None;
+        let mut path = None;
+        let mut ok = None;
+        let mut errors = Vec::<ErrorArm>::new();
+        let mut map = None;
+        let mut response = None;
+        let mut none: Option<Ident> = None;
+        let mut paginate = false;
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+
+            if key == "error" {
+                let content;
+                parenthesized!(content in input);
+                let arm: ErrorArm = content.parse()?;
+
+                if let Some(x) = errors.iter().find(|x| x.status == arm.status) {
+                    let msg = format!("status `{}` is already mapped to `{}`", x.status, x.variant);
+                    return Err(syn::Error::new(arm.status.span(), msg));
+                }
+
+                errors.push(arm);
+            } else if key == "paginate" {
+                paginate = true;
+            } else {
+                input.parse::<Token![=]>()?;
+
+                match key.to_string().as_str() {
+                    "namespace" => namespace = Some(input.parse()?),
+                    "method" => method = Some(input.parse()?),
+                    "path" => path = Some(input.parse()?),
+                    "ok" => ok = Some(input.parse()?),
+                    "map" => map = Some(input.parse()?),
+                    "response" => response = Some(input.parse()?),
+                    "none" => none = Some(input.parse()?),
+                    _ => {
+                        let msg = "expected one of `namespace`, `method`, `path`, `ok`, `map`, `response`, `none`, `paginate`, `error(..)`";
+                        return Err(syn::Error::new(key.span(), msg));
+                    }
+                }
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        let missing =
+            |name: &str| syn::Error::new(Span::call_site(), format!("missing `{name} = ..`"));
+
+        let ok: Ident = ok.ok_or_else(|| missing("ok"))?;
+
+        if let Some(none) = none.as_ref().filter(|x| **x == ok) {
+            let msg = format!("status `{none}` is already used for `ok`");
+            return Err(syn::Error::new(none.span(), msg));
+        }
+
+        Ok(Self {
+            namespace: namespace.ok_or_else(|| missing("namespace"))?,
+            method: method.ok_or_else(|| missing("method"))?,
+            path: path.ok_or_else(|| missing("path"))?,
+            ok,
+            errors,
+            map,
+            response,
+            none,
+            paginate,
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Placement {
+    Path,
+    Query,
+    Body,
+    Header,
+}
+
+impl Placement {
+    fn from_attr(attr: &Attribute) -> Option<Self> {
+        let ident = attr.path.get_ident()?;
+
+        match ident.to_string().as_str() {
+            "path" => Some(Self::Path),
+            "query" => Some(Self::Query),
+            "body" => Some(Self::Body),
+            "header" => Some(Self::Header),
+            _ => None,
+        }
+    }
+
+    fn method(self) -> Ident {
+        let x = match self {
+            Self::Path => "path",
+            Self::Query => "query",
+            Self::Body => "body",
+            Self::Header => "header",
+        };
+
+        Ident::new(x, Span::call_site())
+    }
+
+    fn variant(self) -> Ident {
+        let x = match self {
+            Self::Path => "Path",
+            Self::Query => "Query",
+            Self::Body => "Body",
+            Self::Header => "Header",
+        };
+
+        Ident::new(x, Span::call_site())
+    }
+
+    fn fallible(self) -> bool {
+        matches!(self, Self::Query | Self::Body)
+    }
+}
+
+struct Argument {
+    ident: Ident,
+    ty: Type,
+    placement: Placement,
+    /// `#[body(flatten)]`, 인자의 필드들을 json body에 바로 넣음
+    flatten: bool,
+    /// `#[query(as = Ty)]`, 보낼 때 `Ty::try_from`으로 바꿔서 보냄
+    as_ty: Option<Type>,
+}
+
+/// `#[body(flatten)]`
+fn parse_flatten(attr: &Attribute) -> syn::Result<bool> {
+    if attr.tokens.is_empty() {
+        return Ok(false);
+    }
+
+    let ident: Ident = attr.parse_args()?;
+
+    if ident != "flatten" {
+        return Err(syn::Error::new(ident.span(), "expected `flatten`"));
+    }
+
+    Ok(true)
+}
+
+/// `#[query(as = payload::BookKind)]`
+fn parse_as(attr: &Attribute) -> syn::Result<Option<Type>> {
+    if attr.tokens.is_empty() {
+        return Ok(None);
+    }
+
+    attr.parse_args_with(|input: ParseStream| {
+        input.parse::<Token![as]>()?;
+        input.parse::<Token![=]>()?;
+        input.parse::<Type>()
+    })
+    .map(Some)
+}
+
+fn parse_arguments(item: &mut ForeignItemFn) -> syn::Result<Vec<Argument>> {
+    let mut arguments = Vec::new();
+
+    for arg in item.sig.inputs.iter_mut() {
+        let pat_type = match arg {
+            FnArg::Typed(x) => x,
+            FnArg::Receiver(x) => {
+                return Err(syn::Error::new(x.span(), "endpoint can not take `self`"));
+            }
+        };
+
+        let ident = match pat_type.pat.as_ref() {
+            Pat::Ident(x) => x.ident.clone(),
+            x => return Err(syn::Error::new(x.span(), "expected an identifier")),
+        };
+
+        let mut placement = None;
+        let mut flatten = false;
+        let mut as_ty = None;
+        for attr in pat_type.attrs.drain(..) {
+            match Placement::from_attr(&attr) {
+                Some(_) if placement.is_some() => {
+                    return Err(syn::Error::new(
+                        attr.span(),
+                        "duplicated placement attribute",
+                    ));
+                }
+                Some(Placement::Body) => {
+                    flatten = parse_flatten(&attr)?;
+                    placement = Some(Placement::Body);
+                }
+                Some(Placement::Query) => {
+                    as_ty = parse_as(&attr)?;
+                    placement = Some(Placement::Query);
+                }
+                Some(_) if !attr.tokens.is_empty() => {
+                    return Err(syn::Error::new(
+                        attr.tokens.span(),
+                        "only `#[body]` and `#[query]` take an argument",
+                    ));
+                }
+                Some(x) => placement = Some(x),
+                None => {
+                    let msg = "expected one of `#[path]`, `#[query]`, `#[body]`, `#[header]`";
+                    return Err(syn::Error::new(attr.span(), msg));
+                }
+            }
+        }
+
+        let placement = placement.ok_or_else(|| {
+            let msg = format!(
+                "`{ident}` needs a placement attribute: `#[path]`, `#[query]`, `#[body]` or `#[header]`"
+            );
+            syn::Error::new(ident.span(), msg)
+        })?;
+
+        arguments.push(Argument {
+            ident,
+            ty: pat_type.ty.as_ref().clone(),
+            placement,
+            flatten,
+            as_ty,
+        });
+    }
+
+    Ok(arguments)
+}
+
+/// `Option < payload :: BookKind >` -> `Option<payload::BookKind>`
+fn type_name(ty: &Type) -> String {
+    let mut x = ty.to_token_stream().to_string();
+
+    for (from, to) in [
+        (" :: ", "::"),
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ,", ","),
+    ] {
+        x = x.replace(from, to);
+    }
+
+    x
+}
+
+/// `/books/:book_id/images` -> `["book_id"]`
+fn parse_path(path: &str) -> Result<Vec<&str>, String> {
+    if !path.starts_with('/') {
+        return Err(format!("path `{path}` must start with `/`"));
+    }
+
+    if let Some(x) = path.chars().find(|x| matches!(x, '?' | '#' | ' ')) {
+        return Err(format!(
+            "path `{path}` can not contain `{x}`; use `#[query]` arguments for querystring"
+        ));
+    }
+
+    let mut placeholders = Vec::new();
+
+    // 마지막 segment는 `/`로 끝나는 path를 위해 비어있어도 됨
+    let segments = path[1..].split('/').collect::<Vec<_>>();
+
+    for (i, segment) in segments.iter().enumerate() {
+        if segment.is_empty() && i + 1 != segments.len() {
+            return Err(format!("path `{path}` has an empty segment"));
+        }
+
+        let name = match segment.strip_prefix(':') {
+            Some(x) => x,
+            None if segment.contains(':') => {
+                return Err(format!(
+                    "placeholder in `{segment}` must take the whole segment like `/:name`"
+                ));
+            }
+            None => continue,
+        };
+
+        let is_ident = name.starts_with(|x: char| x.is_ascii_lowercase() || x == '_')
+            && name
+                .chars()
+                .all(|x| x.is_ascii_lowercase() || x.is_ascii_digit() || x == '_');
+
+        if !is_ident {
+            return Err(format!(
+                "placeholder `:{name}` must be a snake_case identifier"
+            ));
+        }
+
+        if placeholders.contains(&name) {
+            return Err(format!("placeholder `:{name}` is duplicated"));
+        }
+
+        placeholders.push(name);
+    }
+
+    Ok(placeholders)
+}
+
+fn check_path(args: &EndpointArgs, arguments: &[Argument]) -> syn::Result<()> {
+    let path = args.path.value();
+
+    let placeholders = parse_path(&path).map_err(|msg| syn::Error::new(args.path.span(), msg))?;
+
+    for name in placeholders.iter() {
+        let matched = arguments
+            .iter()
+            .any(|x| x.placement == Placement::Path && x.ident == name);
+
+        if !matched {
+            let msg =
+                format!("path placeholder `:{name}` has no matching `#[path] {name}` argument");
+            return Err(syn::Error::new(args.path.span(), msg));
+        }
+    }
+
+    for x in arguments.iter().filter(|x| x.placement == Placement::Path) {
+        let name = x.ident.to_string();
+
+        if !placeholders.contains(&name.as_str()) {
+            let msg = format!("`#[path] {name}` has no matching `:{name}` in `{path}`");
+            return Err(syn::Error::new(x.ident.span(), msg));
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn expand(args: EndpointArgs, mut item: ForeignItemFn) -> syn::Result<TokenStream> {
+    let arguments = parse_arguments(&mut item)?;
+
+    check_path(&args, &arguments)?;
+
+    let EndpointArgs {
+        namespace,
+        method,
+        path,
+        ok,
+        errors,
+        map,
+        response,
+        none,
+        paginate,
+    } = args;
+
+    let attrs = &item.attrs;
+    let vis = &item.vis;
+    let fn_ident = &item.sig.ident;
+
+    let ret_ty = match &item.sig.output {
+        ReturnType::Default => syn::parse_quote!(()),
+        ReturnType::Type(_, ty) => ty.as_ref().clone(),
+    };
+    let error_ty = quote!(crate::api::#namespace::error::Error);
+
+    let arg_idents = arguments.iter().map(|x| &x.ident).collect::<Vec<_>>();
+    let arg_tys = arguments.iter().map(|x| &x.ty).collect::<Vec<_>>();
+
+    let inputs = into_args(Punctuated::<FnArg, token::Comma>::from_iter(
+        arguments
+            .iter()
+            .map(|Argument { ident, ty, .. }| -> FnArg { syn::parse_quote!(#ident: #ty) }),
+    ));
+
+    let push_parameters = arguments.iter().map(|x| {
+        let ident = &x.ident;
+        let name = ident.to_string();
+        let method = if x.flatten {
+            format_ident!("body_flatten")
+        } else {
+            x.placement.method()
+        };
+        let try_ = x.placement.fallible().then(|| quote!(?));
+
+        let value = match &x.as_ty {
+            Some(as_ty) if option_inner_ty(&x.ty).is_some() => {
+                quote!(#ident.map(<#as_ty>::try_from).transpose()?)
+            }
+            Some(as_ty) => quote!(<#as_ty>::try_from(#ident)?),
+            None => quote!(#ident),
+        };
+
+        quote_spanned! {x.ty.span()=>
+            parameters.#method(#name, &#value)#try_;
+        }
+    });
+
+    // path 인자는 `Display`로 치환되므로 scalar 타입만 허용함
+    let assert_path_parameters = arguments
+        .iter()
+        .filter(|x| x.placement == Placement::Path)
+        .map(|x| {
+            let ty = &x.ty;
+
+            quote_spanned! {ty.span()=>
+                assert_path_parameter::<#ty>();
+            }
+        });
+
+    let metadata_parameters = arguments.iter().map(|x| {
+        let name = x.ident.to_string();
+        let ty = type_name(&x.ty);
+        let placement = x.placement.variant();
+        let flatten = x.flatten;
+        // 문서에는 실제로 보내는 타입을 씀
+        let schema_ty = match &x.as_ty {
+            Some(as_ty) if option_inner_ty(&x.ty).is_some() => quote!(Option<#as_ty>),
+            Some(as_ty) => quote!(#as_ty),
+            None => x.ty.to_token_stream(),
+        };
+
+        quote! {
+            crate::api::endpoint::Parameter {
+                name: #name,
+                ty: #ty,
+                placement: crate::api::endpoint::Placement::#placement,
+                flatten: #flatten,
+                #[cfg(feature = "openapi")]
+                schema: crate::api::openapi::schema_for::<#schema_ty>,
+            }
+        }
+    });
+
+    let metadata_errors = errors.iter().map(|x| {
+        let ErrorArm {
+            status,
+            variant,
+            message,
+            ..
+        } = x;
+        let variant = variant.to_string();
+
+        quote! {
+            crate::api::endpoint::ErrorStatus {
+                status: ::http::StatusCode::#status,
+                variant: #variant,
+                message: #message,
+            }
+        }
+    });
+
+    let metadata = {
+        let namespace = namespace.to_string();
+        let name = fn_ident.to_string();
+        let method = method.to_string();
+        let ok_ty = type_name(&ret_ty);
+
+        // unit이나 `Bytes`는 json body가 없음
+        let response = match response.as_ref() {
+            Some(ty) => quote!(Some(crate::api::openapi::schema_for::<#ty>)),
+            None if is_unit_ty(&ret_ty) || is_bytes_ty(&ret_ty) => quote!(None),
+            None => quote!(Some(crate::api::openapi::schema_for::<#ret_ty>)),
+        };
+
+        quote! {
+            pub const ENDPOINT: crate::api::Endpoint = crate::api::Endpoint {
+                namespace: #namespace,
+                name: #name,
+                method: #method,
+                path: #path,
+                parameters: &[#(#metadata_parameters),*],
+                ok: ::http::StatusCode::#ok,
+                ok_ty: #ok_ty,
+                #[cfg(feature = "openapi")]
+                response: #response,
+                errors: &[#(#metadata_errors),*],
+            };
+        }
+    };
+
+    let error_variants = errors.iter().map(|x| {
+        let ErrorArm {
+            variant,
+            ty,
+            message,
+            ..
+        } = x;
+        let field = ty.as_ref().map(|ty| quote!((#ty)));
+
+        quote! {
+            #[error(#message)]
+            #variant #field,
+        }
+    });
+
+    let error_arm = |x: &ErrorArm| {
+        let ErrorArm {
+            status,
+            variant,
+            ty,
+            map,
+            ..
+        } = x;
+
+        let Some(ty) = ty else {
+            return quote! {
+                ::http::StatusCode::#status => Err(Error::#variant.into()),
+            };
+        };
+
+        let deserialize = match map {
+            Some(map) => quote! {
+                let map_fn: fn(::bytes::Bytes) -> Result<#ty, BaseError> = #map;
+                let x = map_fn(buf)?;
+            },
+            None => quote! {
+                let x: #ty = serde_json::from_slice(&buf).map_err(BaseError::JsonDeserialize)?;
+            },
+        };
+
+        quote! {
+            ::http::StatusCode::#status => {
+                let buf = resp.bytes().await.map_err(BaseError::BodyRead)?;
+                #deserialize
+
+                Err(Error::#variant(x).into())
+            }
+        }
+    };
+    let error_arms = errors.iter().map(error_arm);
+
+    let ok_arm = if is_unit_ty(&ret_ty) {
+        quote!(Ok(()))
+    } else {
+        let deserialize = match map {
+            Some(map) => quote! {
+                let map_fn: fn(::bytes::Bytes) -> Result<#ret_ty, BaseError> = #map;
+
+                Ok(map_fn(buf)?)
+            },
+            None => quote! {
+                let deserialized: #ret_ty =
+                    serde_json::from_slice(&buf).map_err(BaseError::JsonDeserialize)?;
+
+                Ok(deserialized)
+            },
+        };
+
+        quote! {{
+            let buf = resp.bytes().await.map_err(BaseError::BodyRead)?;
+
+            #[cfg(debug_assertions)]
+            {
+                ::log::debug!("{}", String::from_utf8_lossy(&buf));
+            }
+
+            #deserialize
+        }}
+    };
+
+    // `Option` 인자는 builder의 setter가 됨
+    let (optional_args, required_args): (Vec<_>, Vec<_>) = arguments
+        .iter()
+        .partition(|x| option_inner_ty(&x.ty).is_some());
+
+    let required_args = required_args
+        .iter()
+        .map(|Argument { ident, ty, .. }| quote!((#ident: #ty)))
+        .collect::<Vec<_>>();
+    let optional_args = optional_args.iter().map(|Argument { ident, ty, .. }| {
+        let ty = option_inner_ty(ty).unwrap();
+        quote!((#ident: #ty))
+    });
+
+    // `none = STATUS`가 있으면 해당 status를 `Ok(None)`으로 반환하는 `<fn>_opt`를 만듦
+    let (opt_fn, opt_execute) = match none.as_ref() {
+        Some(none) => {
+            let opt_ident = format_ident!("{}_opt", fn_ident);
+            let doc = format!(" `{fn_ident}`과 같지만 `{none}`이면 `Ok(None)`을 반환함");
+
+            let required_inputs = into_args(Punctuated::<FnArg, token::Comma>::from_iter(
+                arguments
+                    .iter()
+                    .filter(|x| option_inner_ty(&x.ty).is_none())
+                    .map(|Argument { ident, ty, .. }| -> FnArg { syn::parse_quote!(#ident: #ty) }),
+            ));
+            let required_idents = arguments
+                .iter()
+                .filter(|x| option_inner_ty(&x.ty).is_none())
+                .map(|x| &x.ident);
+
+            let error_arms = errors.iter().filter(|x| x.status != *none).map(error_arm);
+
+            let opt_fn = quote! {
+                #[cfg(feature = "client")]
+                impl<'a> crate::client::#namespace<'a> {
+                    #[doc = #doc]
+                    pub async fn #opt_ident(self, #required_inputs) -> Result<Option<#ret_ty>, #error_ty> {
+                        self.#fn_ident(#(#required_idents),*).send_opt().await
+                    }
+                }
+
+                #[doc = #doc]
+                #[allow(clippy::too_many_arguments)]
+                #vis async fn #opt_ident(
+                    base_url: impl Into<String>,
+                    token: impl Into<crate::api::Token<'_>>,
+                    #inputs
+                ) -> Result<Option<#ret_ty>, #error_ty> {
+                    #fn_ident::execute_opt(base_url.into(), token.into(), #(#arg_idents.into()),*).await
+                }
+            };
+
+            let opt_execute = quote! {
+                impl Builder<'_> {
+                    #[doc = #doc]
+                    pub async fn send_opt(self) -> Result<Option<#ret_ty>, #error_ty> {
+                        execute_opt(self.base_url, self.token, #(self.#arg_idents),*).await
+                    }
+                }
+
+                async fn handle_opt(resp: Response) -> Result<Option<#ret_ty>, #error_ty> {
+                    match resp.status() {
+                        ::http::StatusCode::#ok => handle(resp).await.map(Some),
+
+                        ::http::StatusCode::#none => Ok(None),
+
+                        #(#error_arms)*
+
+                        code => Err(BaseError::from_status(code, resp).await),
+                    }
+                }
+
+                #[allow(clippy::too_many_arguments)]
+                pub async fn execute_opt(
+                    base_url: String,
+                    token: Token<'_>,
+                    #(#arg_idents: #arg_tys),*
+                ) -> Result<Option<#ret_ty>, #error_ty> {
+                    let resp = send_request(&base_url, &token, #(#arg_idents),*).await?;
+
+                    response(token, resp, handle_opt).await
+                }
+            };
+
+            (opt_fn, opt_execute)
+        }
+        None => Default::default(),
+    };
+
+    let paginate = if paginate {
+        expand_paginate(&arguments, &ret_ty, &error_ty)?
+    } else {
+        TokenStream::new()
+    };
+
+    Ok(quote! {
+        #[cfg(feature = "client")]
+        crate::api::macros::impl_namespace!(#namespace, #fn_ident, [#(#required_args),*]);
+
+        #(#attrs)*
+        #[allow(clippy::too_many_arguments)]
+        #vis async fn #fn_ident(
+            base_url: impl Into<String>,
+            token: impl Into<crate::api::Token<'_>>,
+            #inputs
+        ) -> Result<#ret_ty, #error_ty> {
+            #fn_ident::execute(base_url.into(), token.into(), #(#arg_idents.into()),*).await
+        }
+
+        #opt_fn
+
+        #vis mod #fn_ident {
+            #[allow(unused_imports)]
+            use crate::api::#namespace::model::*;
+            #[allow(unused_imports)]
+            use crate::api::#namespace::def::*;
+            use crate::api::prelude::*;
+
+            #[derive(Debug, thiserror::Error)]
+            pub enum Error {
+                #(#error_variants)*
+            }
+
+            #metadata
+
+            impl_builder!(
+                #namespace,
+                [#(#required_args),*],
+                [#(#optional_args),*],
+                [#(#arg_idents),*],
+                #ret_ty
+            );
+
+            #[allow(clippy::too_many_arguments)]
+            async fn send_request(
+                base_url: &str,
+                token: &Token<'_>,
+                #(#arg_idents: #arg_tys),*
+            ) -> Result<Response, BaseError> {
+                #[allow(dead_code)]
+                fn assert_path_parameter<T: PathParameter>() {}
+                #(#assert_path_parameters)*
+
+                #[allow(unused_mut)]
+                let mut parameters = Parameters::default();
+
+                #(#push_parameters)*
+
+                ::log::debug!("parameters = {parameters:?}");
+
+                let req = request(::http::Method::#method, base_url, #path, token, parameters)?;
+
+                send(req).await
+            }
+
+            async fn handle(resp: Response) -> Result<#ret_ty, #error_ty> {
+                match resp.status() {
+                    ::http::StatusCode::#ok => #ok_arm,
+
+                    #(#error_arms)*
+
+                    code => Err(BaseError::from_status(code, resp).await),
+                }
+            }
+
+            #[allow(clippy::too_many_arguments)]
+            pub async fn execute(
+                base_url: String,
+                token: Token<'_>,
+                #(#arg_idents: #arg_tys),*
+            ) -> Result<#ret_ty, #error_ty> {
+                let resp = send_request(&base_url, &token, #(#arg_idents),*).await?;
+
+                response(token, resp, handle).await
+            }
+
+            #[allow(clippy::too_many_arguments)]
+            pub async fn execute_with_response(
+                base_url: String,
+                token: Token<'_>,
+                #(#arg_idents: #arg_tys),*
+            ) -> Result<crate::api::Response<#ret_ty>, #error_ty> {
+                let started = ::std::time::Instant::now();
+                let resp = send_request(&base_url, &token, #(#arg_idents),*).await?;
+
+                response_with(started, token, resp, handle).await
+            }
+
+            #opt_execute
+
+            #paginate
+        }
+    })
+}
+
+/// `Builder::paginate`, `Builder::stream`
+fn expand_paginate(
+    arguments: &[Argument],
+    ret_ty: &Type,
+    error_ty: &TokenStream,
+) -> syn::Result<TokenStream> {
+    let item_ty = inner_ty(ret_ty, "Vec").ok_or_else(|| {
+        syn::Error::new(ret_ty.span(), "`paginate` requires a `Vec<T>` return type")
+    })?;
+
+    let find = |name: &str| {
+        arguments.iter().find(|x| x.ident == name).ok_or_else(|| {
+            let msg = format!("`paginate` requires a `{name}` argument");
+            syn::Error::new(Span::call_site(), msg)
+        })
+    };
+
+    // (builder에서 꺼낼 때, builder에 넣을 때)
+    let accessors = |x: &Argument| {
+        let ident = &x.ident;
+
+        if option_inner_ty(&x.ty).is_some() {
+            (quote!(self.#ident), quote!(Some(#ident)))
+        } else {
+            (quote!(Some(self.#ident)), quote!(#ident))
+        }
+    };
+
+    let (get_page, set_page) = accessors(find("page")?);
+    let (get_per_page, set_per_page) = accessors(find("per_page")?);
+
+    Ok(quote! {
+        impl<'a> Builder<'a> {
+            /// 모든 페이지를 차례로 불러오는 `Paginator`
+            pub fn paginate(self) -> crate::api::Paginator<'a, #item_ty, #error_ty> {
+                let (start_page, start_per_page): (Option<usize>, Option<usize>) =
+                    (#get_page, #get_per_page);
+
+                let paginator = crate::api::Paginator::new(move |page, per_page| {
+                    let mut builder = self.clone();
+                    builder.page = #set_page;
+                    builder.per_page = #set_per_page;
+                    builder.send()
+                });
+
+                let paginator = match start_page {
+                    Some(page) => paginator.page(page),
+                    None => paginator,
+                };
+
+                match start_per_page {
+                    Some(per_page) => paginator.per_page(per_page),
+                    None => paginator,
+                }
+            }
+
+            /// 모든 페이지의 항목을 차례로 반환하는 `Stream`
+            pub fn stream(self) -> crate::api::PaginatorStream<'a, #item_ty, #error_ty> {
+                self.paginate().stream()
+            }
+        }
+    })
+}
+
+/// `Option<T>`의 `T`
+fn option_inner_ty(ty: &Type) -> Option<&Type> {
+    inner_ty(ty, "Option")
+}
+
+/// `Wrapper<T>`의 `T`
+fn inner_ty<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(ty_path) = ty else {
+        return None;
+    };
+    let last = ty_path.path.segments.last()?;
+
+    match &last.arguments {
+        PathArguments::AngleBracketed(x) if last.ident == wrapper && x.args.len() == 1 => {
+            match x.args.first()? {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[test]
+fn test_type_name() {
+    let ty: Type = syn::parse_quote!(HashMap<(String, String), Vec<model::Book>>);
+
+    assert_eq!(
+        type_name(&ty),
+        "HashMap<(String, String), Vec<model::Book>>"
+    );
+}
+
+#[test]
+fn test_option_inner_ty() {
+    let ty: Type = syn::parse_quote!(Option<payload::BookSortBy>);
+    assert_eq!(
+        option_inner_ty(&ty).map(type_name).as_deref(),
+        Some("payload::BookSortBy")
+    );
+
+    let ty: Type = syn::parse_quote!(Vec<Option<u32>>);
+    assert!(option_inner_ty(&ty).is_none());
+}
+
+#[test]
+fn test_parse_path() {
+    assert_eq!(parse_path("/books").unwrap(), Vec::<&str>::new());
+    assert_eq!(
+        parse_path("/books/:book_id/images/:file_name").unwrap(),
+        vec!["book_id", "file_name"]
+    );
+    assert_eq!(parse_path("/users/@me/").unwrap(), Vec::<&str>::new());
+
+    assert!(parse_path("books").is_err());
+    assert!(parse_path("/books//images").is_err());
+    assert!(parse_path("/books?page=1").is_err());
+    assert!(parse_path("/books/:").is_err());
+    assert!(parse_path("/books/:bookId").is_err());
+    assert!(parse_path("/books/id:book_id").is_err());
+    assert!(parse_path("/books/:book_id/:book_id").is_err());
+}
+
+#[test]
+fn test_parse_as() {
+    let attr: Attribute = syn::parse_quote!(#[query(as = payload::BookKind)]);
+    let x = parse_as(&attr).unwrap();
+    assert_eq!(
+        x.as_ref().map(type_name).as_deref(),
+        Some("payload::BookKind")
+    );
+
+    let attr: Attribute = syn::parse_quote!(#[query]);
+    assert!(parse_as(&attr).unwrap().is_none());
+
+    let attr: Attribute = syn::parse_quote!(#[query(with = payload::BookKind)]);
+    assert!(parse_as(&attr).is_err());
+}
+
+#[test]
+fn test_parse_error_arm() {
+    let x: ErrorArm = syn::parse_str(r#"NOT_FOUND => NotFoundBook, "Not found book""#).unwrap();
+    assert!(x.ty.is_none() && x.map.is_none());
+
+    let x: ErrorArm =
+        syn::parse_str(r#"BAD_REQUEST => InvalidBook(String), "Invalid book: {0}""#).unwrap();
+    assert_eq!(x.ty.as_ref().map(type_name).as_deref(), Some("String"));
+    assert!(x.map.is_none());
+
+    let x: ErrorArm = syn::parse_str(
+        r#"BAD_REQUEST => InvalidBook(String), "Invalid book: {0}", map = |buf| Ok(parse(buf))"#,
+    )
+    .unwrap();
+    assert!(x.map.is_some());
+
+    assert!(
+        syn::parse_str::<ErrorArm>(r#"NOT_FOUND => NotFoundBook, "x", map = |buf| Ok(buf)"#)
+            .is_err()
+    );
+    assert!(
+        syn::parse_str::<ErrorArm>(r#"NOT_FOUND => NotFoundBook(u8), "x", response = u8"#).is_err()
+    );
+}
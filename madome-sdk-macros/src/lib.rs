@@ -1,3 +1,5 @@
+mod endpoint;
+
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::ToTokens;
@@ -5,16 +7,26 @@ use syn::{
     parse::{Parse, Parser},
     punctuated::Punctuated,
     token, AngleBracketedGenericArguments, Block, Expr, ExprCall, ExprPath, ExprTuple, FnArg,
-    GenericArgument, Item, ItemFn, PatType, Path, PathArguments, PathSegment, ReturnType,
-    Signature, Stmt, TraitBound, TraitBoundModifier, Type, TypeImplTrait, TypeParamBound,
+    ForeignItemFn, GenericArgument, Item, ItemFn, PatType, Path, PathArguments, PathSegment,
+    ReturnType, Signature, Stmt, TraitBound, TraitBoundModifier, Type, TypeImplTrait,
+    TypeParamBound,
 };
 
 fn is_num(ty_str: &str) -> bool {
-    match ty_str {
-        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => true,
-        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => true,
-        _ => false,
-    }
+    matches!(
+        ty_str,
+        "i8" | "i16"
+            | "i32"
+            | "i64"
+            | "i128"
+            | "isize"
+            | "u8"
+            | "u16"
+            | "u32"
+            | "u64"
+            | "u128"
+            | "usize"
+    )
 }
 
 fn is_num_ty(ty: &Type) -> bool {
@@ -23,8 +35,7 @@ fn is_num_ty(ty: &Type) -> bool {
     // matches!(ty, Type::Path(path) if is_num(&path.clone().into_token_stream().to_string()))
 }
 
-#[allow(dead_code)]
-fn is_bytes_ty(ty: &Type) -> bool {
+pub(crate) fn is_bytes_ty(ty: &Type) -> bool {
     // println!("{}", ty.clone().into_token_stream().to_string());
 
     match ty {
@@ -93,6 +104,32 @@ fn impl_into_ty(ty: &Type) -> Type {
     })
 }
 
+pub(crate) fn into_args(
+    inputs: Punctuated<FnArg, token::Comma>,
+) -> Punctuated<FnArg, token::Comma> {
+    let args = inputs.into_iter().map(|x| match x {
+        FnArg::Typed(ref pt) => {
+            // println!("{}", pt.ty.clone().into_token_stream().to_string());
+            // println!("{}", is_impl_into(&pt.ty));
+
+            let is_num = is_num_ty(&pt.ty);
+            let is_into = is_impl_into(&pt.ty);
+
+            if is_num || is_into {
+                x
+            } else {
+                FnArg::Typed(PatType {
+                    ty: Box::new(impl_into_ty(&pt.ty)),
+                    ..pt.clone()
+                })
+            }
+        }
+        _ => x,
+    });
+
+    Punctuated::from_iter(args)
+}
+
 #[proc_macro_attribute]
 pub fn impl_into_args(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let item = syn::Item::parse.parse(item).unwrap();
@@ -101,30 +138,9 @@ pub fn impl_into_args(_attr: TokenStream, item: TokenStream) -> TokenStream {
         Item::Fn(item_fn) => {
             // println!("{}\n", item_fn.clone().into_token_stream().to_string());
 
-            let args = item_fn.sig.inputs.clone().into_iter().map(|x| match x {
-                FnArg::Typed(ref pt) => {
-                    // println!("{}", pt.ty.clone().into_token_stream().to_string());
-                    // println!("{}", is_impl_into(&pt.ty));
-
-                    let is_num = is_num_ty(&pt.ty);
-                    let is_into = is_impl_into(&pt.ty);
-
-                    if is_num || is_into {
-                        x
-                    } else {
-                        let arg = FnArg::Typed(PatType {
-                            ty: Box::new(impl_into_ty(&pt.ty)),
-                            ..pt.clone()
-                        });
-                        arg
-                    }
-                }
-                _ => x,
-            });
-
             let item_fn = ItemFn {
                 sig: Signature {
-                    inputs: Punctuated::from_iter(args),
+                    inputs: into_args(item_fn.sig.inputs),
                     ..item_fn.sig
                 },
                 ..item_fn
@@ -138,7 +154,17 @@ pub fn impl_into_args(_attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
-fn is_unit_ty(ty: &Type) -> bool {
+#[proc_macro_attribute]
+pub fn endpoint(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(attr as endpoint::EndpointArgs);
+    let item = syn::parse_macro_input!(item as ForeignItemFn);
+
+    endpoint::expand(args, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+pub(crate) fn is_unit_ty(ty: &Type) -> bool {
     let ty_st = ty.clone().into_token_stream().to_string();
 
     ty_st == "()"
@@ -230,22 +256,22 @@ pub fn ret_ty_or_unit(_attr: TokenStream, item: TokenStream) -> TokenStream {
             let x = match ret {
                 ReturnType::Default => Wrap::Origin(Ty::Unit),
                 ReturnType::Type(_r, ty) => {
-                    if is_unit_ty(&ty) {
+                    if is_unit_ty(ty) {
                         Wrap::Origin(Ty::Unit)
-                    } else if is_result_unit_ty(&ty) {
+                    } else if is_result_unit_ty(ty) {
                         Wrap::Result(Ty::Unit)
                     /* } else if is_bytes_ty(&ty) {
                         Wrap::Origin(Ty::Bytes(ty.clone()))
                     } else if is_result_bytes_ty(&ty) {
                         Wrap::Result(Ty::Bytes(ty.clone())) */
-                    } else if unwrap_result(&ty).is_some() {
+                    } else if unwrap_result(ty).is_some() {
                         Wrap::Result(Ty::Other)
                     } else {
                         Wrap::Origin(Ty::Other)
                     }
                 } /* (
                       is_unit_ty(&ty),
-                      is_result_unit_ty(&ty),
+                      is_result_unit_ty(ty),
                       is_bytes_ty(&ty),
                       is_resul,
                   ) */
@@ -14,61 +14,27 @@ pub fn check_internal(headers: &http::HeaderMap) -> Result<(), crate::api::auth:
     }
 }
 
-define_request! {
-    auth,
-    create_authcode,
-    (POST, "/auth/code"),
-    Json,
-    [email: String],
-    [
-        #[error("Not Found User")]
-        NotFoundUser,
-        #[error("Too Many Created Authcode")]
-        TooManyCreatedAuthcode
-    ],
-    [
-        StatusCode::NOT_FOUND => |_resp: Response| async {
-            Error::NotFoundUser
-        },
-        StatusCode::TOO_MANY_REQUESTS => |_resp: Response| async {
-            Error::TooManyCreatedAuthcode
-        },
-    ],
-    StatusCode::CREATED => ()
-}
+#[endpoint(
+    namespace = auth,
+    method = POST,
+    path = "/auth/code",
+    ok = CREATED,
+    error(NOT_FOUND => NotFoundUser, "Not Found User"),
+    error(TOO_MANY_REQUESTS => TooManyCreatedAuthcode, "Too Many Created Authcode"),
+)]
+pub async fn create_authcode(#[body] email: String);
 
-define_request! {
-    auth,
-    create_token_pair,
-    (POST, "/auth/token"),
-    Json,
-    [email: String, code: String],
-    [
-        #[error("Not Found Authcode or User")]
-        NotFoundAuthcodeOrUser
-    ],
-    [],
-    StatusCode::CREATED => ()
-}
+#[endpoint(
+    namespace = auth,
+    method = POST,
+    path = "/auth/token",
+    ok = CREATED,
+    error(NOT_FOUND => NotFoundAuthcodeOrUser, "Not Found Authcode or User"),
+)]
+pub async fn create_token_pair(#[body] email: String, #[body] code: String);
 
-define_request! {
-    auth,
-    check_access_token,
-    (GET, "/auth/token"),
-    Querystring,
-    [role: Option<u8>],
-    [],
-    [],
-    StatusCode::OK => UserId
-}
+#[endpoint(namespace = auth, method = GET, path = "/auth/token", ok = OK)]
+pub async fn check_access_token(#[query] role: Option<u8>) -> UserId;
 
-define_request! {
-    auth,
-    refresh_token_pair,
-    (PATCH, "/auth/token"),
-    Nothing,
-    [],
-    [],
-    [],
-    StatusCode::OK => ()
-}
+#[endpoint(namespace = auth, method = PATCH, path = "/auth/token", ok = OK)]
+pub async fn refresh_token_pair();
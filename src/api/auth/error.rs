@@ -7,5 +7,10 @@ extend_error![
     CreateAuthcode(
         #[from]
         def::create_authcode::Error
+    ),
+    #[error("{0}")]
+    CreateTokenPair(
+        #[from]
+        def::create_token_pair::Error
     )
 ];
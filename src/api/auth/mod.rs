@@ -5,6 +5,15 @@ pub mod model;
 pub use def::*;
 pub use error::Error;
 
+use crate::api::Endpoint;
+
+pub static ENDPOINTS: &[Endpoint] = &[
+    create_authcode::ENDPOINT,
+    create_token_pair::ENDPOINT,
+    check_access_token::ENDPOINT,
+    refresh_token_pair::ENDPOINT,
+];
+
 /* use http::{header, Method, StatusCode};
 use reqwest::Client;
 use util::http::{Cookie, SetCookie};
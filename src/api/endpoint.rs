@@ -0,0 +1,127 @@
+use http::StatusCode;
+
+/// 요청 인자가 들어가는 위치
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Placement {
+    Path,
+    Query,
+    Body,
+    Header,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Parameter {
+    pub name: &'static str,
+    /// `Option<payload::BookKind>`
+    pub ty: &'static str,
+    pub placement: Placement,
+    /// json body에 인자의 필드들이 바로 들어감
+    pub flatten: bool,
+    #[cfg(feature = "openapi")]
+    pub schema: super::openapi::SchemaFn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorStatus {
+    pub status: StatusCode,
+    /// `Error`의 variant 이름
+    pub variant: &'static str,
+    pub message: &'static str,
+}
+
+/// `#[endpoint]`로 정의된 요청의 정보
+#[derive(Debug, Clone, Copy)]
+pub struct Endpoint {
+    pub namespace: &'static str,
+    pub name: &'static str,
+    pub method: &'static str,
+    /// `/books/:book_id`
+    pub path: &'static str,
+    pub parameters: &'static [Parameter],
+    pub ok: StatusCode,
+    /// 성공했을 때 반환되는 타입
+    pub ok_ty: &'static str,
+    /// 성공했을 때 json body의 schema
+    #[cfg(feature = "openapi")]
+    pub response: Option<super::openapi::SchemaFn>,
+    pub errors: &'static [ErrorStatus],
+}
+
+impl Endpoint {
+    pub fn parameters_in(&self, placement: Placement) -> impl Iterator<Item = &Parameter> {
+        self.parameters
+            .iter()
+            .filter(move |x| x.placement == placement)
+    }
+}
+
+/// SDK로 요청할 수 있는 모든 endpoint
+pub fn endpoints() -> impl Iterator<Item = &'static Endpoint> {
+    use super::{auth, library, user};
+
+    auth::ENDPOINTS
+        .iter()
+        .chain(user::ENDPOINTS)
+        .chain(library::ENDPOINTS)
+}
+
+#[test]
+fn test_endpoints_are_unique() {
+    let mut names = std::collections::HashSet::new();
+
+    for endpoint in endpoints() {
+        assert!(
+            names.insert((endpoint.namespace, endpoint.name)),
+            "{}::{} is registered twice",
+            endpoint.namespace,
+            endpoint.name
+        );
+
+        for placeholder in endpoint.path.split('/').filter_map(|x| x.strip_prefix(':')) {
+            assert!(
+                endpoint
+                    .parameters_in(Placement::Path)
+                    .any(|x| x.name == placeholder),
+                "{}::{} has no path parameter for `:{placeholder}`",
+                endpoint.namespace,
+                endpoint.name
+            );
+        }
+    }
+}
+
+/// `#[endpoint]`를 붙인 fn이 모두 `ENDPOINTS`에 있는지 확인함
+#[test]
+fn test_endpoints_are_registered() {
+    let sources = [
+        ("auth", include_str!("auth/def.rs")),
+        ("user", include_str!("user/def.rs")),
+        ("library", include_str!("library/def.rs")),
+    ];
+
+    for (namespace, source) in sources {
+        let defined = source
+            .split("#[endpoint(")
+            .skip(1)
+            .map(|x| {
+                let (_, rest) = x.split_once("pub async fn ").unwrap();
+                rest.split(|x: char| !(x.is_alphanumeric() || x == '_'))
+                    .next()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let registered = endpoints()
+            .filter(|x| x.namespace == namespace)
+            .map(|x| x.name)
+            .collect::<Vec<_>>();
+
+        for name in defined.iter() {
+            assert!(
+                registered.contains(name),
+                "{namespace}::{name} is not registered in `{namespace}::ENDPOINTS`"
+            );
+        }
+        assert_eq!(defined.len(), registered.len(), "{namespace}");
+    }
+}
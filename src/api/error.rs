@@ -24,10 +24,27 @@ pub enum BaseError {
     #[error("Querystring Sereialize: {0}")]
     QuerystringSerialize(serde_qs::Error),
 
+    #[error("Path Serialize: {0}")]
+    PathSerialize(String),
+
+    #[error("Body Read: {0}")]
+    BodyRead(reqwest::Error),
+
     #[error("Reqwest: {0}")]
     Reqwest(#[from] reqwest::Error),
+
+    #[error("Transport: {0}")]
+    Transport(String),
+
+    #[error("{0}")]
+    UnknownKind(#[from] UnknownKindError),
 }
 
+/// 모르는 kind는 서버가 받을 수 없으므로 payload로 바꿀 수 없음
+#[derive(Debug, Clone, Copy, thiserror::Error)]
+#[error("Unknown kind can not be sent")]
+pub struct UnknownKindError;
+
 impl BaseError {
     /// match 표현식에서 가장 마지막에 사용해야함
     pub async fn from_status<E>(code: StatusCode, resp: Response) -> E
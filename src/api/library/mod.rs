@@ -5,3 +5,14 @@ pub mod payload;
 
 pub use def::*;
 pub use error::Error;
+
+use crate::api::Endpoint;
+
+pub static ENDPOINTS: &[Endpoint] = &[
+    get_book_by_id::ENDPOINT,
+    get_books::ENDPOINT,
+    get_books_by_ids::ENDPOINT,
+    get_books_by_tags::ENDPOINT,
+    get_book_image_list::ENDPOINT,
+    get_book_image::ENDPOINT,
+];
@@ -1,22 +1,323 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum BookKind {
     Manga,
     Doujinshi,
     GameCg,
     ArtistCg,
+    /// 새로 생긴 kind, 모르는 kind가 와도 역직렬화에 실패하지 않음
+    #[serde(other)]
+    #[cfg_attr(feature = "openapi", schemars(skip))]
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Language {
+    Korean,
+    Japanese,
+    English,
+    Chinese,
+    /// 모르는 언어는 그대로 들고 있음
+    Other(String),
+}
+
+impl Language {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Korean => "korean",
+            Self::Japanese => "japanese",
+            Self::English => "english",
+            Self::Chinese => "chinese",
+            Self::Other(x) => x,
+        }
+    }
+}
+
+impl From<String> for Language {
+    fn from(x: String) -> Self {
+        match x.trim().to_lowercase().as_str() {
+            "korean" => Self::Korean,
+            "japanese" => Self::Japanese,
+            "english" => Self::English,
+            "chinese" => Self::Chinese,
+            _ => Self::Other(x),
+        }
+    }
+}
+
+impl From<&str> for Language {
+    fn from(x: &str) -> Self {
+        x.to_string().into()
+    }
+}
+
+impl From<Language> for String {
+    fn from(x: Language) -> Self {
+        match x {
+            Language::Other(x) => x,
+            x => x.as_str().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum TagKind {
+    Female,
+    Male,
+    Artist,
+    Group,
+    Series,
+    Character,
+    Misc,
+    /// 모르는 kind는 그대로 들고 있음
+    Other(String),
+}
+
+impl TagKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Female => "female",
+            Self::Male => "male",
+            Self::Artist => "artist",
+            Self::Group => "group",
+            Self::Series => "series",
+            Self::Character => "character",
+            Self::Misc => "misc",
+            Self::Other(x) => x,
+        }
+    }
+
+    /// 공백을 지우고 소문자로 바꾼 뒤 `parody`, `tag` 같은 별칭을 합침
+    fn normalize(self) -> Self {
+        let Self::Other(x) = self else {
+            return self;
+        };
+
+        match x.trim().to_lowercase().as_str() {
+            "parody" => Self::Series,
+            "tag" => Self::Misc,
+            x => x.into(),
+        }
+    }
+}
+
+/// 받은 값을 그대로 씀, 정규화는 `Tag::new`에서만 함
+impl From<String> for TagKind {
+    fn from(x: String) -> Self {
+        match x.as_str() {
+            "female" => Self::Female,
+            "male" => Self::Male,
+            "artist" => Self::Artist,
+            "group" => Self::Group,
+            "series" => Self::Series,
+            "character" => Self::Character,
+            "misc" => Self::Misc,
+            _ => Self::Other(x),
+        }
+    }
+}
+
+impl From<&str> for TagKind {
+    fn from(x: &str) -> Self {
+        x.to_string().into()
+    }
+}
+
+impl From<TagKind> for String {
+    fn from(x: TagKind) -> Self {
+        match x {
+            TagKind::Other(x) => x,
+            x => x.as_str().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for TagKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `(kind, name)` tuple로 주고 받음
+///
+/// 받은 태그는 그대로 두고, `Tag::new`나 `"female:big_breasts".parse()`로 만든 태그만 kind와 name을 정규화함
+///
+/// 비교와 hash는 정규화한 값으로 하므로 받은 태그와 만든 태그를 섞어 써도 됨
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "(TagKind, String)", into = "(TagKind, String)")]
+pub struct Tag {
+    pub kind: TagKind,
+    pub name: String,
+}
+
+impl Tag {
+    pub fn new(kind: impl Into<TagKind>, name: impl AsRef<str>) -> Self {
+        Self {
+            kind: kind.into().normalize(),
+            name: normalize_tag_name(name.as_ref()),
+        }
+    }
+
+    /// kind와 name을 정규화한 태그
+    pub fn normalized(&self) -> Self {
+        Self::new(self.kind.clone(), &self.name)
+    }
+}
+
+impl PartialEq for Tag {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (self.normalized(), other.normalized());
+
+        a.kind == b.kind && a.name == b.name
+    }
+}
+
+impl Eq for Tag {}
+
+impl Hash for Tag {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let Self { kind, name } = self.normalized();
+
+        kind.hash(state);
+        name.hash(state);
+    }
+}
+
+/// 소문자로 바꾸고 `_`를 공백으로 바꿈
+fn normalize_tag_name(name: &str) -> String {
+    name.to_lowercase()
+        .replace('_', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid tag: {0}")]
+pub struct ParseTagError(String);
+
+/// `kind:name`, kind가 없으면 `misc`
+///
+/// `:` 앞뒤가 비어있으면 에러
+impl FromStr for Tag {
+    type Err = ParseTagError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tag = match s.split_once(':') {
+            Some((kind, _)) if kind.trim().is_empty() => return Err(ParseTagError(s.to_string())),
+            Some((kind, name)) => Self::new(kind, name),
+            None => Self::new(TagKind::Misc, s),
+        };
+
+        if tag.name.is_empty() {
+            return Err(ParseTagError(s.to_string()));
+        }
+
+        Ok(tag)
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.kind, self.name)
+    }
+}
+
+impl From<(TagKind, String)> for Tag {
+    fn from((kind, name): (TagKind, String)) -> Self {
+        Self { kind, name }
+    }
+}
+
+impl From<(String, String)> for Tag {
+    fn from((kind, name): (String, String)) -> Self {
+        Self {
+            kind: kind.into(),
+            name,
+        }
+    }
+}
+
+impl From<(&str, &str)> for Tag {
+    fn from((kind, name): (&str, &str)) -> Self {
+        Self {
+            kind: kind.into(),
+            name: name.to_string(),
+        }
+    }
+}
+
+impl From<Tag> for (TagKind, String) {
+    fn from(x: Tag) -> Self {
+        (x.kind, x.name)
+    }
+}
+
+impl From<Tag> for (String, String) {
+    fn from(x: Tag) -> Self {
+        (x.kind.into(), x.name)
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl schemars::JsonSchema for Language {
+    fn schema_name() -> String {
+        "Language".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl schemars::JsonSchema for TagKind {
+    fn schema_name() -> String {
+        "TagKind".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl schemars::JsonSchema for Tag {
+    fn schema_name() -> String {
+        "Tag".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        <(String, String)>::json_schema(gen)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct Book {
     pub id: u32,
     pub title: String,
-    pub tags: Vec<(String, String)>,
+    pub tags: Vec<Tag>,
     pub kind: BookKind,
-    pub language: String,
+    pub language: Language,
     pub page: usize,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -30,12 +331,9 @@ fn test_serialize_book() {
     let v = Book {
         id: 123456,
         title: "title".to_string(),
-        tags: vec![
-            ("female".to_string(), "loli".to_string()),
-            ("female".to_string(), "anal".to_string()),
-        ],
+        tags: vec![("female", "loli").into(), ("female", "anal").into()],
         kind: BookKind::Doujinshi,
-        language: "korean".to_string(),
+        language: Language::Korean,
         page: 34,
         created_at: now,
         updated_at: now,
@@ -54,12 +352,9 @@ fn test_serialize_book_in_hashmap() {
     let v = Book {
         id: 123456,
         title: "title".to_string(),
-        tags: vec![
-            ("female".to_string(), "loli".to_string()),
-            ("female".to_string(), "anal".to_string()),
-        ],
+        tags: vec![("female", "loli").into(), ("female", "anal").into()],
         kind: BookKind::Doujinshi,
-        language: "korean".to_string(),
+        language: Language::Korean,
         page: 34,
         created_at: now,
         updated_at: now,
@@ -71,3 +366,79 @@ fn test_serialize_book_in_hashmap() {
 
     println!("{r}");
 }
+
+#[test]
+fn test_tag() {
+    let tag = "Female:Big_Breasts".parse::<Tag>().unwrap();
+    assert_eq!(tag, Tag::new(TagKind::Female, "big breasts"));
+    assert_eq!(tag.to_string(), "female:big breasts");
+
+    assert_eq!("loli".parse::<Tag>().unwrap().kind, TagKind::Misc);
+    assert_eq!(
+        " Parody :touhou".parse::<Tag>().unwrap().kind,
+        TagKind::Series
+    );
+    assert!("female:".parse::<Tag>().is_err());
+    assert!(":loli".parse::<Tag>().is_err());
+    assert!(" :loli".parse::<Tag>().is_err());
+    assert!(":".parse::<Tag>().is_err());
+
+    // tuple로 주고 받고, 모르는 kind나 별칭도 그대로 둠
+    let tag: Tag = serde_json::from_str(r#"["language", "Korean"]"#).unwrap();
+    assert_eq!(tag.kind, TagKind::Other("language".to_string()));
+    assert_eq!(tag.name, "Korean");
+    assert_eq!(
+        serde_json::to_string(&tag).unwrap(),
+        r#"["language","Korean"]"#
+    );
+
+    let tag: Tag = serde_json::from_str(r#"["parody", "touhou"]"#).unwrap();
+    assert_eq!(tag.kind, TagKind::Other("parody".to_string()));
+    assert_eq!(
+        serde_json::to_string(&tag).unwrap(),
+        r#"["parody","touhou"]"#
+    );
+    assert_eq!(Tag::new("Parody", "touhou").kind, TagKind::Series);
+}
+
+#[test]
+fn test_tag_eq() {
+    use std::collections::HashMap;
+
+    let parsed = "female:big_breasts".parse::<Tag>().unwrap();
+    let received: Tag = serde_json::from_str(r#"["female", "Big_Breasts"]"#).unwrap();
+
+    // 받은 값은 그대로 두고 비교만 정규화해서 함
+    assert_eq!(received.name, "Big_Breasts");
+    assert_eq!(received.normalized().name, "big breasts");
+    assert_eq!(parsed, received);
+    assert_eq!(
+        Tag::from(("parody", "touhou")),
+        Tag::new("series", "touhou")
+    );
+    assert_ne!(parsed, Tag::new("male", "big breasts"));
+
+    let books = HashMap::from([(received, vec![1])]);
+    assert_eq!(books.get(&parsed), Some(&vec![1]));
+}
+
+#[test]
+fn test_unknown_kind() {
+    let book: Book = serde_json::from_str(
+        r#"{
+            "id": 1,
+            "title": "title",
+            "tags": [["female", "loli"]],
+            "kind": "webtoon",
+            "language": "esperanto",
+            "page": 1,
+            "created_at": "2022-01-01T00:00:00Z",
+            "updated_at": "2022-01-01T00:00:00Z"
+        }"#,
+    )
+    .unwrap();
+
+    assert!(matches!(book.kind, BookKind::Unknown));
+    assert_eq!(book.language, Language::Other("esperanto".to_string()));
+    assert_eq!(Language::from("Korean"), Language::Korean);
+}
@@ -1,6 +1,12 @@
 use serde::Serialize;
 
-#[derive(Debug, Serialize)]
+use crate::api::UnknownKindError;
+
+use super::model;
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "openapi", schemars(rename = "BookKindPayload"))]
 #[serde(rename_all = "kebab-case")]
 pub enum BookKind {
     Manga,
@@ -9,7 +15,34 @@ pub enum BookKind {
     ArtistCg,
 }
 
-#[derive(Debug, Serialize)]
+/// `get_books`의 `kind`를 보낼 때 씀
+impl TryFrom<model::BookKind> for BookKind {
+    type Error = UnknownKindError;
+
+    fn try_from(x: model::BookKind) -> Result<Self, Self::Error> {
+        match x {
+            model::BookKind::Manga => Ok(Self::Manga),
+            model::BookKind::Doujinshi => Ok(Self::Doujinshi),
+            model::BookKind::GameCg => Ok(Self::GameCg),
+            model::BookKind::ArtistCg => Ok(Self::ArtistCg),
+            model::BookKind::Unknown => Err(UnknownKindError),
+        }
+    }
+}
+
+impl From<BookKind> for model::BookKind {
+    fn from(x: BookKind) -> Self {
+        match x {
+            BookKind::Manga => Self::Manga,
+            BookKind::Doujinshi => Self::Doujinshi,
+            BookKind::GameCg => Self::GameCg,
+            BookKind::ArtistCg => Self::ArtistCg,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum BookSortBy {
     IdDesc,
@@ -18,6 +51,7 @@ pub enum BookSortBy {
 }
 
 /* #[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub enum BookSortByWithoutRandom {
     #[serde(rename = "created_desc")]
     CreatedAtDesc,
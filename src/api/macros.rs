@@ -14,211 +14,87 @@ macro_rules! extend_error {
 
 pub(crate) use extend_error;
 
-#[cfg(feature = "client")]
-macro_rules! impl_namespace {
-    ($namespace:ident, $fn:ident, [$(($arg_id:ident: $arg_ty:ty)),*$(,)?], $ret_ty:ty) => {
-        impl $crate::client::$namespace<'_> {
-            #[impl_into_args]
-            pub async fn $fn(self, $($arg_id: $arg_ty),*) -> Result<$ret_ty, $crate::api::$namespace::error::Error> {
-                $fn(self.base_url, self.token, $($arg_id.into()),*).await
-            }
-        }
-    };
-}
-
-#[cfg(feature = "client")]
-pub(crate) use impl_namespace;
-
-macro_rules! define_request {
-    ($namespace:ident,
-    $fn:ident,
-    ($method:expr, $path:expr),
-    $parameter_kind:expr,
-    [$($arg_id:ident: $arg_ty:ty),*$(,)?],
-    [$($err_member:tt)*],
-    [$($err_code:path => $err:expr),*$(,)?],
-    $ok_code:path => $ret_ty:ty
-    $(, $return_mapper:expr)?) =>
-        {
-        #[cfg(feature = "client")]
-        impl_namespace!($namespace, $fn, [$(($arg_id: $arg_ty)),*], $ret_ty);
-
-        #[impl_into_args]
-        #[allow(clippy::too_many_arguments)]
-        pub async fn $fn(base_url: impl Into<String>, token: impl Into<Token<'_>>, $($arg_id: $arg_ty),*) -> Result<$ret_ty, $crate::api::$namespace::error::Error> {
-            $fn::execute(base_url.into(), token.into(), $($arg_id.into()),*).await
+/// 필수 인자는 `Builder::new`로, `Option` 인자는 setter로 받는 요청 builder
+///
+/// `.send().await` 혹은 `.await`로 요청을 보냄
+macro_rules! impl_builder {
+    (
+        $namespace:ident,
+        [$(($arg_id:ident: $arg_ty:ty)),*$(,)?],
+        [$(($opt_id:ident: $opt_ty:ty)),*$(,)?],
+        [$($id:ident),*$(,)?],
+        $ret_ty:ty
+    ) => {
+        #[derive(Clone)]
+        #[must_use = "builder does nothing until `.send()` or `.await`"]
+        pub struct Builder<'a> {
+            base_url: String,
+            token: Token<'a>,
+            $($arg_id: $arg_ty,)*
+            $($opt_id: Option<$opt_ty>,)*
         }
 
-        pub mod $fn {
-            use http::StatusCode;
-
-            #[allow(unused_imports)]
-            use $crate::api::$namespace::model::*;
-            use $crate::api::$namespace::def::*;
-            // use $crate::api::macros::*;
-
-            use ParameterKind::*;
-
-            #[derive(Debug, thiserror::Error)]
-            pub enum Error {
-                $($err_member)*
+        impl<'a> Builder<'a> {
+            #[impl_into_args]
+            pub fn new(
+                base_url: impl Into<String>,
+                token: impl Into<Token<'a>>,
+                $($arg_id: $arg_ty),*
+            ) -> Self {
+                Self {
+                    base_url: base_url.into(),
+                    token: token.into(),
+                    $($arg_id: $arg_id.into(),)*
+                    $($opt_id: None,)*
+                }
             }
 
-            define_request!(@def_fn
-                $namespace,
-                $method,
-                $path,
-                $parameter_kind,
-                [$(($arg_id, $arg_ty)),*],
-                [$($err_code => $err),*],
-                $ok_code,
-                $ret_ty,
-                $($return_mapper)?
-            );
-        }
-    };
-
-    (@def_qs [$($arg_id:ident, $arg_ty:ty),*$(,)?]) => {
-        #[derive(Debug, ::serde::Serialize)]
-        #[serde(rename_all = "kebab-case")]
-        pub(crate) struct QuerystringParameters {
             $(
-                $arg_id: $arg_ty,
+                #[impl_into_args]
+                pub fn $opt_id(mut self, $opt_id: $opt_ty) -> Self {
+                    self.$opt_id = Some($opt_id.into());
+                    self
+                }
             )*
-        }
 
-        pub(crate) fn qs_parameters($($arg_id: $arg_ty),*) -> QuerystringParameters {
-            QuerystringParameters {
-                $($arg_id,)*
+            pub async fn send(self) -> Result<$ret_ty, $crate::api::$namespace::error::Error> {
+                execute(self.base_url, self.token, $(self.$id),*).await
             }
-        }
-    };
-
-    (@def_json [$($arg_id:ident, $arg_ty:ty),*$(,)?]) => {
-        #[derive(Debug, ::serde::Serialize)]
-        #[serde(rename_all = "snake_case")]
-        pub(crate) struct JsonParameters {
-            $(
-                $arg_id: $arg_ty,
-            )*
-        }
 
-        pub(crate) fn json_parameters($($arg_id: $arg_ty),*) -> JsonParameters {
-            JsonParameters {
-                $($arg_id,)*
+            /// body와 함께 status, header, 걸린 시간을 반환함
+            pub async fn with_response(
+                self,
+            ) -> Result<$crate::api::Response<$ret_ty>, $crate::api::$namespace::error::Error> {
+                execute_with_response(self.base_url, self.token, $(self.$id),*).await
             }
         }
-    };
 
-    (@def_path [$($arg_id:ident, $arg_ty:ty),*$(,)?]) => {
-        #[derive(Debug, ::serde::Serialize)]
-        pub(crate) struct PathParameters {
-            $(
-                $arg_id: $arg_ty,
-            )*
-        }
+        impl<'a> std::future::IntoFuture for Builder<'a> {
+            type Output = Result<$ret_ty, $crate::api::$namespace::error::Error>;
+            type IntoFuture = std::pin::Pin<Box<dyn std::future::Future<Output = Self::Output> + Send + 'a>>;
 
-        pub(crate) fn path_parameters($($arg_id: $arg_ty),*) -> PathParameters {
-            PathParameters {
-                $($arg_id,)*
+            fn into_future(self) -> Self::IntoFuture {
+                Box::pin(self.send())
             }
         }
     };
+}
 
-    (@def_fn
-        $namespace:ident,
-        $method:expr,
-        $path:expr,
-        $parameter_kind:expr,
-        [$(($arg_id:ident, $arg_ty:ty)),*$(,)?],
-        [$($err_code:path => $err:expr),*$(,)?],
-        $ok_code:path,
-        $ret_ty:ty,
-        $($return_mapper:expr)?) => {
-        define_request!(@def_qs [$($arg_id, $arg_ty),*]);
-        define_request!(@def_json [$($arg_id, $arg_ty),*]);
-        define_request!(@def_path [$($arg_id, $arg_ty),*]);
-
-        #[allow(clippy::too_many_arguments)]
-        pub async fn execute(base_url: String, token: Token<'_>, $($arg_id: $arg_ty),*) -> Result<$ret_ty, $crate::api::$namespace::error::Error> {
-            let req = match $parameter_kind {
-                ParameterKind::Querystring => {
-                    let parameter = qs_parameters($($arg_id,)*);
-                    ::log::debug!("qs_parameter = {parameter:?}");
-                    request($method, &base_url, $path, &token, $parameter_kind, Some(parameter))
-                },
-                ParameterKind::Json => {
-                    let parameter = json_parameters($($arg_id,)*);
-                    ::log::debug!("json_parameter = {parameter:?}");
-                    request($method, &base_url, $path, &token, $parameter_kind, Some(parameter))
-                },
-                ParameterKind::Path => {
-                    let parameter = path_parameters($($arg_id,)*);
-                    ::log::debug!("path_parameter = {parameter:?}");
-                    let path = ::serde_path::to_string($path, &parameter).unwrap();
-                    request($method, &base_url, &path, &token, $parameter_kind, None::<()>)
-                },
-                ParameterKind::Nothing => {
-                    request($method, &base_url, $path, &token, $parameter_kind, None::<()>)
-                },
-            }?;
-
-            let resp = req
-                .send()
-                .await
-                .map_err(BaseError::Reqwest)?;
-
-            response(token, resp, |resp| async {
-                match resp.status() {
-                    $ok_code => {
-                        #[ret_ty_or_unit]
-                        #[allow(unused_variables)]
-                        async fn deserialize(resp: Response) -> Result<$ret_ty, BaseError> {
-                            let buf = resp
-                                .bytes()
-                                .await
-                                .unwrap_or_default();
-
-                            // ::log::debug!("deserializing...");
-
-                            #[cfg(debug_assertions)] {
-                                let buf: &[u8] = buf.as_ref();
-                                ::log::debug!("{}", String::from_utf8(buf.to_vec()).unwrap());
-                            }
-
-                            #[allow(unused_mut)]
-                            let mut maybe_map_fn: Option<fn(::bytes::Bytes) -> Result<$ret_ty, BaseError>> = None;
-
-                            $(
-                                maybe_map_fn.replace($return_mapper);
-                            )?
-
-                            match maybe_map_fn {
-                                Some(map_fn) => Ok(map_fn(buf)?),
-                                None => {
-                                    let deserialized = serde_json::from_slice(&buf)
-                                        .map_err(BaseError::JsonDeserialize)?;
-
-                                    Ok(deserialized)
-                                }
-                            }
-                        }
-
-                        Ok(deserialize(resp).await?)
-                    }
-
-                    $($err_code => {
-                        use self::Error;
-
-                        Err(<$crate::api::$namespace::error::Error>::from($err(resp).await))
-                    },)*
+pub(crate) use impl_builder;
 
-                    code => Err(BaseError::from_status(code, resp).await),
-                }
-            })
-            .await
+#[cfg(feature = "client")]
+macro_rules! impl_namespace {
+    ($namespace:ident, $fn:ident, [$(($arg_id:ident: $arg_ty:ty)),*$(,)?]) => {
+        impl<'a> $crate::client::$namespace<'a> {
+            // `is_liked`처럼 endpoint 이름이 `is_`로 시작할 수 있음
+            #[allow(clippy::wrong_self_convention)]
+            #[impl_into_args]
+            pub fn $fn(self, $($arg_id: $arg_ty),*) -> $fn::Builder<'a> {
+                $fn::Builder::new(self.base_url, self.token, $($arg_id),*)
+            }
         }
     };
 }
 
-pub(crate) use define_request;
+#[cfg(feature = "client")]
+pub(crate) use impl_namespace;
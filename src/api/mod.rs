@@ -1,14 +1,22 @@
 mod error;
 mod macros;
+mod paginate;
+mod response;
 mod token;
 
 pub mod auth;
 pub mod cookie;
+pub mod endpoint;
 pub mod header;
 pub mod library;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 pub mod user;
 
-pub use error::BaseError;
+pub use endpoint::{endpoints, Endpoint};
+pub use error::{BaseError, UnknownKindError};
+pub use paginate::{Paginator, PaginatorStream, DEFAULT_PER_PAGE};
+pub use response::Response;
 pub use token::{Token, TokenBehavior};
 
 #[allow(unused_imports, dead_code)]
@@ -16,11 +24,13 @@ pub(crate) mod prelude {
     pub(crate) use reqwest::{Request, Response};
     pub(crate) use serde::{Deserialize, Serialize};
 
+    pub(crate) use madome_sdk_macros::endpoint;
     pub(crate) use madome_sdk_macros::impl_into_args;
-    pub(crate) use madome_sdk_macros::ret_ty_or_unit;
 
     pub(crate) use super::error::BaseError;
-    pub(crate) use super::http::{request, response, ParameterKind};
+    pub(crate) use super::http::{
+        request, response, response_with, send, Parameters, PathParameter,
+    };
     pub(crate) use super::macros::*;
     pub(crate) use super::token::Token;
 
@@ -34,77 +44,201 @@ pub(crate) mod prelude {
 }
 
 mod http {
-    use std::future::Future;
+    use std::{fmt::Display, future::Future, time::Instant};
 
     use http::{header, Method};
     use reqwest::{Client, RequestBuilder, Response};
     use serde::Serialize;
+    use serde_json::{Map, Value};
 
     use super::error::BaseError;
     use super::token::Token;
 
-    /* pub(crate) enum NeedWrapping {
-        Yes,
-        No,
+    /// path에 치환될 수 있는 scalar 타입
+    pub(crate) trait PathParameter: Display {}
+
+    macro_rules! impl_path_parameter {
+        ($($ty:ty),*$(,)?) => {
+            $(
+                impl PathParameter for $ty {}
+            )*
+        };
+    }
+
+    impl_path_parameter![
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        usize,
+        i8,
+        i16,
+        i32,
+        i64,
+        i128,
+        isize,
+        String,
+        &str,
+        uuid::Uuid,
+    ];
+
+    impl<L, R> PathParameter for either::Either<L, R>
+    where
+        L: PathParameter,
+        R: PathParameter,
+    {
+    }
+
+    /// 요청 인자를 위치(path, querystring, json body, header)에 따라 모아둠
+    #[derive(Debug, Default)]
+    pub(crate) struct Parameters {
+        path: Vec<(&'static str, String)>,
+        query: Map<String, Value>,
+        body: Map<String, Value>,
+        header: Vec<(String, String)>,
     }
 
-    pub(crate) enum ParameterKind {
-        Path(NeedWrapping),
-        Querystring(NeedWrapping),
-        Json(NeedWrapping),
-        Nothing,
-    } */
-
-    #[allow(dead_code)]
-    pub(crate) enum ParameterKind {
-        Path,
-        Querystring,
-        Json,
-        Nothing,
+    impl Parameters {
+        pub(crate) fn path(&mut self, name: &'static str, value: &impl PathParameter) {
+            self.path.push((name, value.to_string()));
+        }
+
+        pub(crate) fn query(
+            &mut self,
+            name: &'static str,
+            value: &impl Serialize,
+        ) -> Result<(), BaseError> {
+            let value = serde_json::to_value(value).map_err(BaseError::JsonSerialize)?;
+
+            if !value.is_null() {
+                // querystring은 kebab-case
+                self.query.insert(name.replace('_', "-"), value);
+            }
+
+            Ok(())
+        }
+
+        pub(crate) fn body(
+            &mut self,
+            name: &'static str,
+            value: &impl Serialize,
+        ) -> Result<(), BaseError> {
+            let value = serde_json::to_value(value).map_err(BaseError::JsonSerialize)?;
+
+            // `None`은 보내지 않음, PATCH에서 바꾸지 않을 필드
+            if !value.is_null() {
+                self.body.insert(name.to_string(), value);
+            }
+
+            Ok(())
+        }
+
+        /// 인자를 `{ "name": .. }`가 아니라 인자의 필드들로 json body에 넣음
+        pub(crate) fn body_flatten(
+            &mut self,
+            name: &'static str,
+            value: &impl Serialize,
+        ) -> Result<(), BaseError> {
+            match serde_json::to_value(value).map_err(BaseError::JsonSerialize)? {
+                Value::Object(map) => {
+                    self.body.extend(map);
+                    Ok(())
+                }
+                _ => Err(BaseError::JsonSerialize(serde::ser::Error::custom(
+                    format!("`{name}` must be serialized to a json object"),
+                ))),
+            }
+        }
+
+        #[allow(dead_code)]
+        pub(crate) fn header(&mut self, name: &'static str, value: &impl Display) {
+            self.header
+                .push((name.replace('_', "-"), value.to_string()));
+        }
+
+        /// `/books/:book_id`의 `:book_id`를 path 인자로 치환함
+        fn to_path(&self, template: &str) -> Result<String, BaseError> {
+            let segments = template
+                .split('/')
+                .map(|segment| match segment.strip_prefix(':') {
+                    Some(name) => self
+                        .path
+                        .iter()
+                        .find(|(x, _)| *x == name)
+                        .map(|(_, value)| encode_path_segment(value))
+                        .ok_or_else(|| {
+                            BaseError::PathSerialize(format!(
+                                "missing path parameter `{name}` in `{template}`"
+                            ))
+                        }),
+                    None => Ok(segment.to_string()),
+                });
+
+            Ok(segments.collect::<Result<Vec<_>, _>>()?.join("/"))
+        }
     }
 
-    pub(crate) fn request<T>(
+    fn encode_path_segment(x: &str) -> String {
+        let mut encoded = String::with_capacity(x.len());
+
+        for b in x.bytes() {
+            match b {
+                b'A'..=b'Z'
+                | b'a'..=b'z'
+                | b'0'..=b'9'
+                | b'-'
+                | b'.'
+                | b'_'
+                | b'~'
+                | b'@'
+                | b':'
+                | b'+' => encoded.push(b as char),
+                b => encoded.push_str(&format!("%{b:02X}")),
+            }
+        }
+
+        encoded
+    }
+
+    pub(crate) fn request(
         method: Method,
         base_url: &str,
         path: &str,
         token: &Token,
-        parameter_kind: ParameterKind,
-        parameter: Option<T>,
-    ) -> Result<RequestBuilder, BaseError>
-    where
-        T: Serialize,
-    {
+        parameters: Parameters,
+    ) -> Result<RequestBuilder, BaseError> {
         let (cookie, token) = token.as_cookie().into();
 
-        let url = format!("{base_url}{path}");
+        let path = parameters.to_path(path)?;
 
-        let req = Client::new();
-        let req = match parameter_kind {
-            ParameterKind::Querystring => {
-                let qs = serde_qs::to_string(parameter.as_ref().unwrap())
-                    .map_err(BaseError::QuerystringSerialize)?;
-                log::debug!("serialized_parameter = {qs}");
-                req.request(method, format!("{url}?{qs}"))
-            }
+        let url = if parameters.query.is_empty() {
+            format!("{base_url}{path}")
+        } else {
+            let qs =
+                serde_qs::to_string(&parameters.query).map_err(BaseError::QuerystringSerialize)?;
+            log::debug!("serialized_querystring = {qs}");
+            format!("{base_url}{path}?{qs}")
+        };
 
-            ParameterKind::Json => {
-                let json = serde_json::to_vec(parameter.as_ref().unwrap())
-                    .map_err(BaseError::JsonSerialize)?;
-                #[cfg(debug_assertions)]
-                {
-                    log::debug!(
-                        "serialized_parameter = {}",
-                        String::from_utf8(json.clone()).unwrap()
-                    );
-                }
-                req.request(method, url)
-                    .header(header::CONTENT_TYPE, "application/json")
-                    .body(json)
+        let mut req = Client::new().request(method, url);
+
+        if !parameters.body.is_empty() {
+            let json = serde_json::to_vec(&parameters.body).map_err(BaseError::JsonSerialize)?;
+            #[cfg(debug_assertions)]
+            {
+                log::debug!("serialized_body = {}", String::from_utf8_lossy(&json));
             }
+            req = req
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(json);
+        }
 
-            ParameterKind::Path | ParameterKind::Nothing => req.request(method, url),
+        for (name, value) in parameters.header {
+            req = req.header(name, value);
         }
-        .header(cookie, token);
+
+        let req = req.header(cookie, token);
 
         #[cfg(test)]
         {
@@ -127,6 +261,17 @@ mod http {
         Ok(req)
     }
 
+    pub(crate) async fn send(req: RequestBuilder) -> Result<Response, BaseError> {
+        #[cfg(feature = "cassette")]
+        if let Some(cassette) = crate::cassette::current() {
+            let req = req.build().map_err(BaseError::Reqwest)?;
+
+            return cassette.send(req).await;
+        }
+
+        req.send().await.map_err(BaseError::Reqwest)
+    }
+
     #[allow(unused_variables)]
     pub(crate) fn response<T, E, F, Fut>(token: Token, resp: Response, f: F) -> Fut
     where
@@ -137,4 +282,46 @@ mod http {
 
         f(resp)
     }
+
+    /// `response`와 같지만 status, header, 걸린 시간을 함께 반환함
+    pub(crate) async fn response_with<T, E, F, Fut>(
+        started: Instant,
+        token: Token<'_>,
+        resp: Response,
+        f: F,
+    ) -> Result<super::Response<T>, E>
+    where
+        F: Fn(Response) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let status = resp.status();
+        let headers = resp.headers().clone();
+
+        let body = response(token, resp, f).await?;
+
+        Ok(super::Response {
+            body,
+            status,
+            headers,
+            elapsed: started.elapsed(),
+        })
+    }
+
+    #[test]
+    fn test_parameters_to_path() {
+        let mut parameters = Parameters::default();
+        parameters.path("book_id", &1234);
+        parameters.path("file_name", &"image 1.png");
+
+        let path = parameters
+            .to_path("/books/:book_id/images/:file_name")
+            .unwrap();
+
+        assert_eq!(path, "/books/1234/images/image%201.png");
+
+        assert!(matches!(
+            parameters.to_path("/books/:book_id/:page"),
+            Err(BaseError::PathSerialize(_))
+        ));
+    }
 }
@@ -0,0 +1,397 @@
+//! SDK에 정의된 endpoint와 model로 OpenAPI 3.1 문서를 만듦
+
+use http::StatusCode;
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::Schema,
+    JsonSchema,
+};
+use serde_json::{json, Map, Value};
+
+use super::endpoint::{endpoints, Endpoint, Parameter, Placement};
+
+pub type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+pub fn schema_for<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+    gen.subschema_for::<T>()
+}
+
+/// OpenAPI 3.1의 기본 dialect, JSON Schema 2020-12
+const JSON_SCHEMA_DIALECT: &str = "https://spec.openapis.org/oas/3.1/dialect/base";
+
+/// `Like`, `History` 처럼 `#[serde(tag = "kind")]`로 구분되는 enum의 tag
+const DISCRIMINATOR: &str = "kind";
+
+pub fn document() -> Value {
+    // schemars는 2020-12를 지원하지 않으므로 2019-09로 만들고 `to_2020_12`로 바꿈
+    let mut gen = SchemaSettings::draft2019_09()
+        .with(|x| {
+            x.definitions_path = "#/components/schemas/".to_string();
+            x.meta_schema = None;
+        })
+        .into_generator();
+
+    // 같은 method, path를 쓰는 endpoint는 하나의 operation으로 합침
+    // e.g. `get_books`, `get_books_by_ids`, `get_books_by_tags`
+    let mut operations = Vec::<((String, &str), Vec<&Endpoint>)>::new();
+
+    for endpoint in endpoints() {
+        let key = (openapi_path(endpoint.path), endpoint.method);
+
+        match operations.iter_mut().find(|(x, _)| *x == key) {
+            Some((_, xs)) => xs.push(endpoint),
+            None => operations.push((key, vec![endpoint])),
+        }
+    }
+
+    let mut paths = Map::new();
+
+    for ((path, method), endpoints) in operations {
+        let item = paths
+            .entry(path)
+            .or_insert_with(|| Value::Object(Map::new()));
+
+        if let Value::Object(item) = item {
+            item.insert(method.to_lowercase(), operation(&mut gen, &endpoints));
+        }
+    }
+
+    let mut schemas = gen
+        .take_definitions()
+        .into_iter()
+        .map(|(name, schema)| (name, to_value(&schema)))
+        .collect::<Map<_, _>>();
+
+    for schema in schemas.values_mut() {
+        add_discriminator(schema);
+        to_2020_12(schema);
+    }
+
+    for item in paths.values_mut() {
+        to_2020_12(item);
+    }
+
+    json!({
+        "openapi": "3.1.0",
+        "jsonSchemaDialect": JSON_SCHEMA_DIALECT,
+        "info": {
+            "title": "Madome API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "tags": [
+            { "name": "auth" },
+            { "name": "user" },
+            { "name": "library" },
+        ],
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "securitySchemes": {
+                "accessToken": {
+                    "type": "apiKey",
+                    "in": "cookie",
+                    "name": super::cookie::MADOME_ACCESS_TOKEN,
+                },
+            },
+        },
+        "security": [{ "accessToken": [] }],
+    })
+}
+
+pub fn to_json() -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&document())
+}
+
+pub fn to_yaml() -> Result<String, serde_yaml::Error> {
+    serde_yaml::to_string(&document())
+}
+
+/// `/books/:book_id` -> `/books/{book_id}`
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|x| match x.strip_prefix(':') {
+            Some(name) => format!("{{{name}}}"),
+            None => x.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn to_value(schema: &Schema) -> Value {
+    serde_json::to_value(schema).unwrap_or_default()
+}
+
+fn is_optional(parameter: &Parameter) -> bool {
+    parameter.ty.starts_with("Option<")
+}
+
+/// `Parameters`가 querystring, header에 쓰는 이름
+fn wire_name(parameter: &Parameter) -> String {
+    match parameter.placement {
+        Placement::Query | Placement::Header => parameter.name.replace('_', "-"),
+        Placement::Path | Placement::Body => parameter.name.to_string(),
+    }
+}
+
+/// 모든 endpoint에 있고 `Option`이 아니어야 필수 인자임
+fn is_required(endpoints: &[&Endpoint], placement: Placement, name: &str) -> bool {
+    placement == Placement::Path
+        || endpoints.iter().all(|endpoint| {
+            endpoint
+                .parameters_in(placement)
+                .any(|x| x.name == name && !is_optional(x))
+        })
+}
+
+fn operation(gen: &mut SchemaGenerator, endpoints: &[&Endpoint]) -> Value {
+    let names = endpoints
+        .iter()
+        .map(|x| format!("`{}::{}`", x.namespace, x.name))
+        .collect::<Vec<_>>();
+
+    let mut parameters = Vec::<Value>::new();
+    let mut body = Map::new();
+    let mut body_required = Vec::<&str>::new();
+    let mut body_flattened = Vec::<Value>::new();
+
+    for parameter in endpoints.iter().flat_map(|x| x.parameters) {
+        let name = wire_name(parameter);
+        let required = is_required(endpoints, parameter.placement, parameter.name);
+        let schema = to_value(&(parameter.schema)(gen));
+
+        let location = match parameter.placement {
+            Placement::Path => "path",
+            Placement::Query => "query",
+            Placement::Header => "header",
+            Placement::Body if parameter.flatten => {
+                if !body_flattened.contains(&schema) {
+                    body_flattened.push(schema);
+                }
+                continue;
+            }
+            Placement::Body => {
+                if required && !body_required.contains(&parameter.name) {
+                    body_required.push(parameter.name);
+                }
+                body.entry(name).or_insert(schema);
+                continue;
+            }
+        };
+
+        let exists = parameters
+            .iter()
+            .any(|x| x["name"] == name.as_str() && x["in"] == location);
+
+        if !exists {
+            parameters.push(json!({
+                "name": name,
+                "in": location,
+                "required": required,
+                "schema": schema,
+            }));
+        }
+    }
+
+    let mut responses = Map::new();
+
+    for endpoint in endpoints {
+        let ok = endpoint.ok.as_u16().to_string();
+
+        let content = match endpoint.response {
+            Some(schema) => {
+                Some(json!({ "application/json": { "schema": to_value(&schema(gen)) } }))
+            }
+            None if endpoint.ok_ty == "Bytes" => Some(json!({
+                "application/octet-stream": {
+                    "schema": { "type": "string", "format": "binary" },
+                },
+            })),
+            None => None,
+        };
+
+        let response = responses
+            .entry(ok)
+            .or_insert_with(|| json!({ "description": reason(endpoint.ok) }));
+
+        if let Some(content) = content {
+            merge_content(response, content);
+        }
+
+        for error in endpoint.errors {
+            let status = error.status.as_u16().to_string();
+
+            match responses.get_mut(&status) {
+                Some(response) => {
+                    let description = response["description"].as_str().unwrap_or_default();
+
+                    if !description.contains(error.message) {
+                        response["description"] =
+                            format!("{description} / {}", error.message).into();
+                    }
+                }
+                None => {
+                    responses.insert(status, json!({ "description": error.message }));
+                }
+            }
+        }
+    }
+
+    // `BaseError::from_status`
+    for status in [
+        StatusCode::BAD_REQUEST,
+        StatusCode::UNAUTHORIZED,
+        StatusCode::FORBIDDEN,
+    ] {
+        responses
+            .entry(status.as_u16().to_string())
+            .or_insert_with(|| json!({ "description": reason(status) }));
+    }
+
+    let mut operation = json!({
+        "operationId": endpoints.iter().map(|x| x.name).collect::<Vec<_>>().join("_or_"),
+        "tags": [endpoints[0].namespace],
+        "description": format!("SDK: {}", names.join(", ")),
+        "parameters": parameters,
+        "responses": responses,
+    });
+
+    // `#[body(flatten)]` 인자는 그 schema를 그대로 합침
+    let mut body_schemas = body_flattened;
+
+    if !body.is_empty() {
+        body_schemas.push(json!({
+            "type": "object",
+            "properties": body,
+            "required": body_required,
+        }));
+    }
+
+    let body_schema = match body_schemas.len() {
+        0 => None,
+        1 => body_schemas.pop(),
+        _ => Some(json!({ "allOf": body_schemas })),
+    };
+
+    if let Some(schema) = body_schema {
+        operation["requestBody"] = json!({
+            "required": true,
+            "content": {
+                "application/json": {
+                    "schema": schema,
+                },
+            },
+        });
+    }
+
+    operation
+}
+
+fn reason(status: StatusCode) -> &'static str {
+    status.canonical_reason().unwrap_or_default()
+}
+
+/// 같은 operation으로 합쳐진 endpoint의 응답이 다르면 `oneOf`로 묶음
+fn merge_content(response: &mut Value, content: Value) {
+    let schema = &content["application/json"]["schema"];
+
+    match response.get_mut("content") {
+        Some(existing) if existing == &content => {}
+        Some(existing) => {
+            let prev = existing["application/json"]["schema"].take();
+
+            let mut one_of = match prev.get("oneOf") {
+                Some(Value::Array(xs)) => xs.clone(),
+                _ => vec![prev],
+            };
+
+            if !one_of.contains(schema) {
+                one_of.push(schema.clone());
+            }
+
+            existing["application/json"]["schema"] = json!({ "oneOf": one_of });
+        }
+        None => {
+            response["content"] = content;
+        }
+    }
+}
+
+/// `#[serde(tag = "kind")]` enum의 `oneOf`에 discriminator를 붙임
+fn add_discriminator(schema: &mut Value) {
+    let tagged = match schema.get("oneOf") {
+        Some(Value::Array(variants)) => variants.iter().all(|x| {
+            x["properties"][DISCRIMINATOR]["enum"]
+                .as_array()
+                .is_some_and(|xs| xs.len() == 1)
+        }),
+        _ => false,
+    };
+
+    if tagged {
+        schema["discriminator"] = json!({ "propertyName": DISCRIMINATOR });
+    }
+}
+
+/// 2019-09의 tuple `items: [..]`를 2020-12의 `prefixItems`로 바꿈
+fn to_2020_12(schema: &mut Value) {
+    match schema {
+        Value::Object(x) => {
+            if x.get("items").is_some_and(Value::is_array) {
+                let items = x.remove("items").unwrap_or_default();
+                x.insert("prefixItems".to_string(), items);
+
+                if let Some(additional) = x.remove("additionalItems") {
+                    x.insert("items".to_string(), additional);
+                }
+            }
+
+            x.values_mut().for_each(to_2020_12);
+        }
+        Value::Array(xs) => xs.iter_mut().for_each(to_2020_12),
+        _ => {}
+    }
+}
+
+#[test]
+fn test_document() {
+    let document = document();
+
+    let get_book_by_id = &document["paths"]["/books/{book_id}"]["get"];
+    assert_eq!(get_book_by_id["parameters"][0]["in"], "path");
+    assert!(get_book_by_id["responses"]["404"].is_object());
+
+    // get_books, get_books_by_ids, get_books_by_tags
+    let get_books = &document["paths"]["/books"]["get"];
+    let schema = &get_books["responses"]["200"]["content"]["application/json"]["schema"];
+    assert_eq!(schema["oneOf"].as_array().map(Vec::len), Some(2));
+
+    assert_eq!(document["jsonSchemaDialect"], JSON_SCHEMA_DIALECT);
+
+    let schemas = &document["components"]["schemas"];
+
+    let like = &schemas["Like"];
+    assert_eq!(like["discriminator"]["propertyName"], DISCRIMINATOR);
+
+    // `#[serde(other)]`로 받기만 하는 `Unknown`은 문서에 없음
+    let kinds = |name: &str| {
+        schemas[name]["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x["properties"][DISCRIMINATOR]["enum"][0].clone())
+            .collect::<Vec<_>>()
+    };
+    for name in ["Like", "History", "Notification"] {
+        assert!(!kinds(name).contains(&json!("unknown")), "{name}");
+    }
+    assert!(!schemas["BookKind"]["enum"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("unknown")));
+
+    // tuple은 2020-12의 `prefixItems`
+    assert!(schemas["Tag"]["prefixItems"].is_array());
+    assert!(!document.to_string().contains("additionalItems"));
+
+    assert!(to_yaml().is_ok());
+}
@@ -0,0 +1,281 @@
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::{future::BoxFuture, Stream};
+
+pub const DEFAULT_PER_PAGE: usize = 25;
+
+type FetchPage<'a, T, E> =
+    Box<dyn FnMut(usize, usize) -> BoxFuture<'a, Result<Vec<T>, E>> + Send + 'a>;
+
+/// `page`, `per_page`를 받는 endpoint를 페이지 단위로 불러와 하나의 `Stream`으로 만듦
+///
+/// 빈 페이지나 요청한 개수보다 적은 항목이 오면 마지막 페이지로 봄
+///
+/// 서버가 `per_page`의 상한을 두고 있다면 그보다 큰 `per_page`는 첫 페이지에서 끝나버리므로,
+/// 요청 횟수를 제한하려면 `max_pages`를 함께 씀
+///
+/// ```ignore
+/// let books = client
+///     .library()
+///     .get_books()
+///     .kind(BookKind::Manga)
+///     .paginate()
+///     .per_page(50)
+///     .prefetch(true)
+///     .stream();
+/// ```
+pub struct Paginator<'a, T, E> {
+    fetch: FetchPage<'a, T, E>,
+    page: usize,
+    per_page: usize,
+    max_pages: Option<usize>,
+    prefetch: bool,
+}
+
+impl<'a, T, E> Paginator<'a, T, E> {
+    /// `fetch(page, per_page)`
+    pub fn new<F, Fut>(mut fetch: F) -> Self
+    where
+        F: FnMut(usize, usize) -> Fut + Send + 'a,
+        Fut: Future<Output = Result<Vec<T>, E>> + Send + 'a,
+    {
+        Self {
+            fetch: Box::new(move |page, per_page| Box::pin(fetch(page, per_page))),
+            page: 1,
+            per_page: DEFAULT_PER_PAGE,
+            max_pages: None,
+            prefetch: false,
+        }
+    }
+
+    /// 시작 페이지, 1부터 시작함
+    pub fn page(mut self, page: usize) -> Self {
+        self.page = page;
+        self
+    }
+
+    /// 0이면 1로 요청함
+    pub fn per_page(mut self, per_page: usize) -> Self {
+        self.per_page = per_page.max(1);
+        self
+    }
+
+    /// 최대 `max_pages`개의 페이지만 불러옴, 마지막 페이지를 알 수 없을 때도 요청이 끝나도록 보장함
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// 현재 페이지를 읽는 동안 다음 페이지를 미리 요청함
+    pub fn prefetch(mut self, prefetch: bool) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+
+    pub fn stream(self) -> PaginatorStream<'a, T, E> {
+        PaginatorStream {
+            fetch: self.fetch,
+            next_page: self.page,
+            per_page: self.per_page,
+            remaining_pages: self.max_pages,
+            prefetch: self.prefetch,
+            buffer: VecDeque::new(),
+            pending: None,
+            fetched: None,
+            done: false,
+        }
+    }
+}
+
+pub struct PaginatorStream<'a, T, E> {
+    fetch: FetchPage<'a, T, E>,
+    next_page: usize,
+    per_page: usize,
+    remaining_pages: Option<usize>,
+    prefetch: bool,
+    buffer: VecDeque<T>,
+    pending: Option<BoxFuture<'a, Result<Vec<T>, E>>>,
+    fetched: Option<Result<Vec<T>, E>>,
+    done: bool,
+}
+
+// pin projection을 하지 않으므로 안전함
+impl<T, E> Unpin for PaginatorStream<'_, T, E> {}
+
+impl<T, E> PaginatorStream<'_, T, E> {
+    fn fetch_next(&mut self) {
+        if self.done || self.pending.is_some() || self.fetched.is_some() {
+            return;
+        }
+
+        match self.remaining_pages.as_mut() {
+            Some(0) => {
+                self.done = true;
+                return;
+            }
+            Some(remaining) => *remaining -= 1,
+            None => {}
+        }
+
+        self.pending = Some((self.fetch)(self.next_page, self.per_page));
+        self.next_page += 1;
+    }
+
+    fn poll_pending(&mut self, cx: &mut Context<'_>) {
+        if let Some(pending) = self.pending.as_mut() {
+            if let Poll::Ready(fetched) = pending.as_mut().poll(cx) {
+                self.pending = None;
+                self.fetched = Some(fetched);
+            }
+        }
+    }
+}
+
+impl<T, E> Stream for PaginatorStream<'_, T, E> {
+    type Item = Result<T, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            this.poll_pending(cx);
+
+            if let Some(x) = this.buffer.pop_front() {
+                if this.prefetch {
+                    this.fetch_next();
+                    this.poll_pending(cx);
+                }
+
+                return Poll::Ready(Some(Ok(x)));
+            }
+
+            match this.fetched.take() {
+                Some(Ok(items)) => {
+                    if items.is_empty() || items.len() < this.per_page {
+                        this.done = true;
+                    }
+
+                    this.buffer.extend(items);
+                    continue;
+                }
+                Some(Err(err)) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(err)));
+                }
+                None => {}
+            }
+
+            if this.pending.is_some() {
+                return Poll::Pending;
+            }
+
+            this.fetch_next();
+
+            if this.pending.is_none() {
+                return Poll::Ready(None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use futures_util::StreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_paginator() {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+
+        let paginator = |requested: Arc<Mutex<Vec<usize>>>| {
+            Paginator::new(move |page, per_page| {
+                requested.lock().unwrap().push(page);
+
+                // 1..=7
+                let items = (1..=7)
+                    .skip((page - 1) * per_page)
+                    .take(per_page)
+                    .collect::<Vec<_>>();
+
+                async move {
+                    if page == 10 {
+                        Err("error")
+                    } else {
+                        Ok(items)
+                    }
+                }
+            })
+            .per_page(3)
+        };
+
+        let xs = paginator(requested.clone())
+            .prefetch(true)
+            .stream()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(xs, (1..=7).map(Ok).collect::<Vec<_>>());
+        assert_eq!(*requested.lock().unwrap(), vec![1, 2, 3]);
+
+        let xs = paginator(requested.clone())
+            .page(2)
+            .max_pages(1)
+            .stream()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(xs, vec![Ok(4), Ok(5), Ok(6)]);
+
+        let xs = paginator(requested)
+            .page(10)
+            .stream()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(xs, vec![Err("error")]);
+    }
+
+    #[tokio::test]
+    async fn test_paginator_terminates() {
+        // per_page를 무시하고 3개씩 주는 서버
+        let fetch = |page: usize, _per_page: usize| async move {
+            Ok::<_, ()>(if page <= 2 { vec![page; 3] } else { Vec::new() })
+        };
+
+        let xs = Paginator::new(fetch)
+            .per_page(0)
+            .stream()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(xs, vec![Ok(1), Ok(1), Ok(1), Ok(2), Ok(2), Ok(2)]);
+
+        let xs = Paginator::new(fetch)
+            .per_page(3)
+            .stream()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(xs.len(), 6);
+
+        // 서버가 per_page를 3으로 제한하면 첫 페이지에서 끝남
+        let xs = Paginator::new(fetch)
+            .per_page(100)
+            .max_pages(5)
+            .stream()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(xs.len(), 3);
+
+        let xs = Paginator::new(|page, _| async move { Ok::<_, ()>(vec![page; 3]) })
+            .per_page(1)
+            .max_pages(2)
+            .stream()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(xs, vec![Ok(1), Ok(1), Ok(1), Ok(2), Ok(2), Ok(2)]);
+    }
+}
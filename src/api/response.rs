@@ -0,0 +1,58 @@
+use std::{ops::Deref, time::Duration};
+
+use http::{header, header::AsHeaderName, HeaderMap, StatusCode};
+
+/// 응답 body와 함께 status, header, 걸린 시간을 담음
+///
+/// `Builder::with_response`로 받을 수 있음
+#[derive(Debug, Clone)]
+pub struct Response<T> {
+    pub body: T,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// 요청을 보낸 뒤 body를 다 읽을 때까지 걸린 시간
+    pub elapsed: Duration,
+}
+
+impl<T> Response<T> {
+    pub fn into_body(self) -> T {
+        self.body
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Response<U> {
+        Response {
+            body: f(self.body),
+            status: self.status,
+            headers: self.headers,
+            elapsed: self.elapsed,
+        }
+    }
+
+    pub fn header(&self, name: impl AsHeaderName) -> Option<&str> {
+        self.headers.get(name).and_then(|x| x.to_str().ok())
+    }
+
+    pub fn etag(&self) -> Option<&str> {
+        self.header(header::ETAG)
+    }
+
+    pub fn last_modified(&self) -> Option<&str> {
+        self.header(header::LAST_MODIFIED)
+    }
+
+    /// 이 요청으로 갱신된 쿠키
+    pub fn set_cookies(&self) -> impl Iterator<Item = &str> {
+        self.headers
+            .get_all(header::SET_COOKIE)
+            .into_iter()
+            .filter_map(|x| x.to_str().ok())
+    }
+}
+
+impl<T> Deref for Response<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.body
+    }
+}
@@ -96,15 +96,18 @@ mod server {
 
             match token_pair {
                 Some(token_pair) => {
-                    {
+                    let r = {
                         let mut resp = self.write();
 
                         resp.set_header(http::header::SET_COOKIE, token_pair)
-                            .unwrap();
 
                         // dropped here
+                    };
+
+                    match r {
+                        Ok(_) => log::debug!("token updated = true"),
+                        Err(err) => log::warn!("token updated = false; {err:?}"),
                     }
-                    log::debug!("token updated = true");
                 }
                 None => log::debug!("token updated = false"),
             }
@@ -1,195 +1,226 @@
 use either::Either;
 use uuid::Uuid;
 
-use crate::api::prelude::*;
-
-use super::model;
-
-/* define_request! {
-    user,
-    create_user,
-    (POST, "/users"),
-    Json,
-    [name: String, email: String, role: Option<u8>],
-    [
-        #[error("Conflict")]
-        Conflict,
-    ],
-    [
-        StatusCode::CONFLICT => |_resp: Response| async {
-            Error::Conflict
-        }
-    ],
-    StatusCode::OK => ()
-} */
-
-define_request! {
-    user,
-    get_user,
-    (GET, "/_/users/:user_id_or_email"),
-    Path,
-    [user_id_or_email: Either<Uuid, String>],
-    [
-        #[error("Not found user")]
-        NotFoundUser,
-    ],
-    [
-        StatusCode::NOT_FOUND => |_resp| async {
-            Error::NotFoundUser
-        }
-    ],
-    StatusCode::OK => model::User
-}
-
-define_request! {
-    user,
-    get_likes,
-    (GET, "/_/users/:user_id/likes"),
-    Path,
-    [user_id: Uuid],
-    [
-        #[error("Not found user")]
-        NotFoundUser,
-    ],
-    [
-        StatusCode::NOT_FOUND => |_resp| async {
-            Error::NotFoundUser
-        }
-    ],
-    StatusCode::OK => Vec<model::Like>
-}
-
-define_request! {
-    user,
-    get_histories,
-    (GET, "/_/users/:user_id/histories"),
-    Path,
-    [user_id: Uuid],
-    [
-        #[error("Not found user")]
-        NotFoundUser,
-    ],
-    [
-        StatusCode::NOT_FOUND => |_resp| async {
-            Error::NotFoundUser
-        }
-    ],
-    StatusCode::OK => Vec<model::History>
-}
-
-/* define_request! {
-    user,
-    get_me,
-    (GET, "/users/@me"),
-    Nothing,
-    [],
-    [], // 404 에러가 있지만 애초에 404에러가 발생하기 전에 인증 에러가 발생함
-    [], // NotFound에러는 핸들링 해줄 이유가 없음
-    StatusCode::OK => model::User
-}
-
-define_request! {
-    user,
-    create_or_update_fcm_token,
-    (PATCH, "/users/@me/fcm-token"),
-    Json,
-    [udid: Uuid, fcm_token: String],
-    [],
-    [],
-    StatusCode::CREATED => ()
-}
-
-define_request! {
-    user,
-    get_likes,
-    (GET, "/users/@me/likes"),
-    Querystring,
-    [
-        kind: Option<payload::LikeKind>,
-        per_page: usize,
-        page: usize,
-        sort_by: Option<payload::LikeSortBy>],
-    [],
-    [],
-    StatusCode::OK => Vec<model::Like>
-}
-
-define_request! {
-    user,
-    create_like,
-    (POST, "/users/@me/likes"),
-    Json,
-    [like: payload::Like],
-    [
-        #[error("Already Exists Like")]
-        AlreadyExistsLike
-    ],
-    [
-        StatusCode::CONFLICT => |_resp: Response| async {
-            Error::AlreadyExistsLike
-        }
-    ],
-    StatusCode::CREATED => ()
-}
-
-define_request! {
-    user,
-    delete_like,
-    (DELETE, "/users/@me/likes"),
-    Json,
-    [like: payload::Like],
-    [
-        #[error("Not Found Like")]
-        NotFoundLike
-    ],
-    [
-        StatusCode::NOT_FOUND => |_resp: Response| async {
-            Error::NotFoundLike
-        }
-    ],
-    StatusCode::NO_CONTENT => ()
-}
-
-define_request! {
-    user,
-    get_notifications,
-    (GET, "/users/@me/notifications"),
-    Nothing,
-    [],
-    [],
-    [],
-    StatusCode::OK => Vec<model::Notification>
-}
-
-define_request! {
-    user,
-    get_histories,
-    (GET, "/users/@me/likes"),
-    Querystring,
-    [
-        kind: Option<payload::LikeKind>,
-        per_page: usize,
-        page: usize,
-        sort_by: Option<payload::LikeSortBy>],
-    [],
-    [],
-    StatusCode::OK => Vec<model::Like>
-}
-
-define_request! {
-    user,
-    create_or_update_history,
-    (POST, "/users/@me/likes"),
-    Json,
-    [like: payload::Like],
-    [
-        #[error("Already Exists History")]
-        AlreadyExistsHistory
-    ],
-    [
-        StatusCode::CONFLICT => |_resp: Response| async {
-            Error::AlreadyExistsHistory
-        }
-    ],
-    StatusCode::CREATED => ()
-} */
+use crate::api::{library, prelude::*};
+
+use super::{model, payload};
+
+#[endpoint(
+    namespace = user,
+    method = POST,
+    path = "/users",
+    ok = OK,
+    error(CONFLICT => Conflict, "Already exists email"),
+)]
+pub async fn create_user(#[body] name: String, #[body] email: String, #[body] role: Option<u8>);
+
+#[endpoint(
+    namespace = user,
+    method = GET,
+    path = "/_/users/:user_id_or_email",
+    ok = OK,
+    none = NOT_FOUND,
+    error(NOT_FOUND => NotFoundUser, "Not found user"),
+)]
+pub async fn get_user(#[path] user_id_or_email: Either<Uuid, String>) -> model::User;
+
+#[endpoint(
+    namespace = user,
+    method = GET,
+    path = "/_/users/:user_id/likes",
+    ok = OK,
+    error(NOT_FOUND => NotFoundUser, "Not found user"),
+)]
+pub async fn get_likes(#[path] user_id: Uuid) -> Vec<model::Like>;
+
+#[endpoint(
+    namespace = user,
+    method = GET,
+    path = "/_/users/:user_id/histories",
+    ok = OK,
+    error(NOT_FOUND => NotFoundUser, "Not found user"),
+)]
+pub async fn get_histories(#[path] user_id: Uuid) -> Vec<model::History>;
+
+/// `role`을 바꾸려면 요청한 유저의 role이 더 높아야 함
+#[endpoint(
+    namespace = user,
+    method = PATCH,
+    path = "/_/users/:user_id_or_email",
+    ok = OK,
+    error(NOT_FOUND => NotFoundUser, "Not found user"),
+)]
+pub async fn update_user(
+    #[path] user_id_or_email: Either<Uuid, String>,
+    #[body] name: Option<String>,
+    #[body] role: Option<u8>,
+) -> model::User;
+
+#[endpoint(
+    namespace = user,
+    method = DELETE,
+    path = "/_/users/:user_id_or_email",
+    ok = NO_CONTENT,
+    error(NOT_FOUND => NotFoundUser, "Not found user"),
+)]
+pub async fn delete_user(#[path] user_id_or_email: Either<Uuid, String>);
+
+/*
+    @me
+*/
+
+// 404 에러가 있지만 애초에 404에러가 발생하기 전에 인증 에러가 발생함
+#[endpoint(namespace = user, method = GET, path = "/users/@me", ok = OK)]
+pub async fn get_me() -> model::User;
+
+#[endpoint(namespace = user, method = PATCH, path = "/users/@me", ok = OK)]
+pub async fn update_me(#[body] name: Option<String>) -> model::User;
+
+#[endpoint(namespace = user, method = DELETE, path = "/users/@me", ok = NO_CONTENT)]
+pub async fn delete_me();
+
+#[endpoint(
+    namespace = user,
+    method = PATCH,
+    path = "/users/@me/fcm-token",
+    ok = CREATED
+)]
+pub async fn create_or_update_fcm_token(#[body] udid: Uuid, #[body] fcm_token: String);
+
+#[endpoint(namespace = user, method = GET, path = "/users/@me/devices", ok = OK)]
+pub async fn get_devices() -> Vec<model::Device>;
+
+#[endpoint(
+    namespace = user,
+    method = DELETE,
+    path = "/users/@me/devices/:udid",
+    ok = NO_CONTENT,
+    error(NOT_FOUND => NotFoundDevice, "Not found device"),
+)]
+pub async fn delete_device(#[path] udid: Uuid);
+
+#[endpoint(
+    namespace = user,
+    method = GET,
+    path = "/users/@me/likes",
+    ok = OK,
+    paginate,
+)]
+pub async fn get_my_likes(
+    #[query] kind: Option<payload::LikeKind>,
+    #[query] per_page: usize,
+    #[query] page: usize,
+    #[query] sort_by: Option<payload::LikeSortBy>,
+) -> Vec<model::Like>;
+
+#[endpoint(
+    namespace = user,
+    method = POST,
+    path = "/users/@me/likes",
+    ok = CREATED,
+    error(CONFLICT => AlreadyExistsLike, "Already exists like"),
+)]
+pub async fn create_like(#[body(flatten)] like: payload::Like);
+
+#[endpoint(
+    namespace = user,
+    method = DELETE,
+    path = "/users/@me/likes",
+    ok = NO_CONTENT,
+    error(NOT_FOUND => NotFoundLike, "Not found like"),
+)]
+pub async fn delete_like(#[body(flatten)] like: payload::Like);
+
+/// 결과는 `likes`와 같은 순서, 이미 있는 좋아요는 `AlreadyExists`
+#[endpoint(
+    namespace = user,
+    method = POST,
+    path = "/users/@me/likes/batch",
+    ok = OK
+)]
+pub async fn create_likes(#[body] likes: Vec<payload::Like>) -> Vec<model::LikeResult>;
+
+/// 결과는 `likes`와 같은 순서, 없는 좋아요는 `NotFound`
+#[endpoint(
+    namespace = user,
+    method = DELETE,
+    path = "/users/@me/likes/batch",
+    ok = OK
+)]
+pub async fn delete_likes(#[body] likes: Vec<payload::Like>) -> Vec<model::LikeResult>;
+
+/// 요청한 작품, 태그 중 좋아요한 것만 돌려줌
+#[endpoint(
+    namespace = user,
+    method = GET,
+    path = "/users/@me/likes/state",
+    ok = OK
+)]
+pub async fn is_liked(
+    #[query] book_ids: Vec<u32>,
+    #[query] book_tags: Vec<library::model::Tag>,
+) -> model::LikeState;
+
+/// `unread`가 `true`면 읽지 않은 알림만
+#[endpoint(
+    namespace = user,
+    method = GET,
+    path = "/users/@me/notifications",
+    ok = OK,
+    paginate,
+)]
+pub async fn get_notifications(
+    #[query] kind: Option<payload::NotificationKind>,
+    #[query] unread: Option<bool>,
+    #[query] per_page: usize,
+    #[query] page: usize,
+    #[query] sort_by: Option<payload::NotificationSortBy>,
+) -> Vec<model::Notification>;
+
+#[endpoint(
+    namespace = user,
+    method = GET,
+    path = "/users/@me/notifications/unread-count",
+    ok = OK
+)]
+pub async fn get_unread_notification_count() -> usize;
+
+#[endpoint(
+    namespace = user,
+    method = POST,
+    path = "/users/@me/notifications/:notification_id/read",
+    ok = NO_CONTENT,
+    error(NOT_FOUND => NotFoundNotification, "Not found notification"),
+)]
+pub async fn read_notification(#[path] notification_id: Uuid);
+
+#[endpoint(
+    namespace = user,
+    method = POST,
+    path = "/users/@me/notifications/read",
+    ok = NO_CONTENT
+)]
+pub async fn read_all_notifications();
+
+#[endpoint(
+    namespace = user,
+    method = GET,
+    path = "/users/@me/histories",
+    ok = OK,
+    paginate,
+)]
+pub async fn get_my_histories(
+    #[query] kind: Option<payload::HistoryKind>,
+    #[query] per_page: usize,
+    #[query] page: usize,
+    #[query] sort_by: Option<payload::HistorySortBy>,
+) -> Vec<model::History>;
+
+#[endpoint(
+    namespace = user,
+    method = POST,
+    path = "/users/@me/histories",
+    ok = CREATED,
+    error(NOT_FOUND => NotFoundBook, "Not found book"),
+)]
+pub async fn create_or_update_history(#[body(flatten)] history: payload::History);
@@ -3,11 +3,6 @@ use crate::api::macros::extend_error;
 use super::def;
 
 extend_error![
-    /* #[error("{0}")]
-    GetMe(
-        #[from]
-        def::get_me::Error
-    ), */
     #[error("{0}")]
     GetUser(
         #[from]
@@ -23,18 +18,57 @@ extend_error![
         #[from]
         def::get_histories::Error
     ),
-    /* #[error("{0}")]
+    #[error("{0}")]
     CreateUser(
         #[from]
         def::create_user::Error
-    ), */
-    /*
+    ),
+    #[error("{0}")]
+    UpdateUser(
+        #[from]
+        def::update_user::Error
+    ),
+    #[error("{0}")]
+    DeleteUser(
+        #[from]
+        def::delete_user::Error
+    ),
+    #[error("{0}")]
+    GetMe(
+        #[from]
+        def::get_me::Error
+    ),
+    #[error("{0}")]
+    UpdateMe(
+        #[from]
+        def::update_me::Error
+    ),
+    #[error("{0}")]
+    DeleteMe(
+        #[from]
+        def::delete_me::Error
+    ),
     #[error("{0}")]
     CreateOrUpdateFcmToken(
         #[from]
         def::create_or_update_fcm_token::Error
     ),
     #[error("{0}")]
+    GetDevices(
+        #[from]
+        def::get_devices::Error
+    ),
+    #[error("{0}")]
+    DeleteDevice(
+        #[from]
+        def::delete_device::Error
+    ),
+    #[error("{0}")]
+    GetMyLikes(
+        #[from]
+        def::get_my_likes::Error
+    ),
+    #[error("{0}")]
     CreateLike(
         #[from]
         def::create_like::Error
@@ -43,5 +77,50 @@ extend_error![
     DeleteLike(
         #[from]
         def::delete_like::Error
-    ) */
+    ),
+    #[error("{0}")]
+    CreateLikes(
+        #[from]
+        def::create_likes::Error
+    ),
+    #[error("{0}")]
+    DeleteLikes(
+        #[from]
+        def::delete_likes::Error
+    ),
+    #[error("{0}")]
+    IsLiked(
+        #[from]
+        def::is_liked::Error
+    ),
+    #[error("{0}")]
+    GetNotifications(
+        #[from]
+        def::get_notifications::Error
+    ),
+    #[error("{0}")]
+    GetUnreadNotificationCount(
+        #[from]
+        def::get_unread_notification_count::Error
+    ),
+    #[error("{0}")]
+    ReadNotification(
+        #[from]
+        def::read_notification::Error
+    ),
+    #[error("{0}")]
+    ReadAllNotifications(
+        #[from]
+        def::read_all_notifications::Error
+    ),
+    #[error("{0}")]
+    GetMyHistories(
+        #[from]
+        def::get_my_histories::Error
+    ),
+    #[error("{0}")]
+    CreateOrUpdateHistory(
+        #[from]
+        def::create_or_update_history::Error
+    ),
 ];
@@ -5,3 +5,32 @@ pub mod payload;
 
 pub use def::*;
 pub use error::Error;
+
+use crate::api::Endpoint;
+
+pub static ENDPOINTS: &[Endpoint] = &[
+    create_user::ENDPOINT,
+    get_user::ENDPOINT,
+    get_likes::ENDPOINT,
+    get_histories::ENDPOINT,
+    update_user::ENDPOINT,
+    delete_user::ENDPOINT,
+    get_me::ENDPOINT,
+    update_me::ENDPOINT,
+    delete_me::ENDPOINT,
+    create_or_update_fcm_token::ENDPOINT,
+    get_devices::ENDPOINT,
+    delete_device::ENDPOINT,
+    get_my_likes::ENDPOINT,
+    create_like::ENDPOINT,
+    delete_like::ENDPOINT,
+    create_likes::ENDPOINT,
+    delete_likes::ENDPOINT,
+    is_liked::ENDPOINT,
+    get_notifications::ENDPOINT,
+    get_unread_notification_count::ENDPOINT,
+    read_notification::ENDPOINT,
+    read_all_notifications::ENDPOINT,
+    get_my_histories::ENDPOINT,
+    create_or_update_history::ENDPOINT,
+];
@@ -2,7 +2,10 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::api::library::model::{Tag, TagKind};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 pub struct User {
     pub id: Uuid,
     pub name: String,
@@ -12,7 +15,18 @@ pub struct User {
     pub updated_at: DateTime<Utc>,
 }
 
+/// 푸시 알림을 받을 기기
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct Device {
+    pub udid: Uuid,
+    pub fcm_token: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Like {
     Book {
@@ -20,28 +34,208 @@ pub enum Like {
         created_at: DateTime<Utc>,
     },
     BookTag {
-        tag_kind: String,
-        tag_name: String,
+        #[serde(flatten, with = "flat_tag")]
+        #[cfg_attr(feature = "openapi", schemars(with = "FlatTag"))]
+        tag: Tag,
         created_at: DateTime<Utc>,
     },
+    /// 새로 생긴 kind, 모르는 kind가 와도 역직렬화에 실패하지 않음
+    #[serde(other)]
+    #[cfg_attr(feature = "openapi", schemars(skip))]
+    Unknown,
+}
+
+impl Like {
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Book { created_at, .. } | Self::BookTag { created_at, .. } => Some(*created_at),
+            Self::Unknown => None,
+        }
+    }
+
+    pub fn tag(&self) -> Option<&Tag> {
+        match self {
+            Self::BookTag { tag, .. } => Some(tag),
+            _ => None,
+        }
+    }
+}
+
+/// 좋아요의 태그는 tuple이 아니라 `tag_kind`, `tag_name` 필드로 주고 받음
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub(crate) struct FlatTag {
+    tag_kind: TagKind,
+    tag_name: String,
+}
+
+pub(crate) mod flat_tag {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{FlatTag, Tag};
+
+    pub fn serialize<S: Serializer>(tag: &Tag, serializer: S) -> Result<S::Ok, S::Error> {
+        FlatTag {
+            tag_kind: tag.kind.clone(),
+            tag_name: tag.name.clone(),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Tag, D::Error> {
+        let FlatTag { tag_kind, tag_name } = FlatTag::deserialize(deserializer)?;
+
+        Ok(Tag {
+            kind: tag_kind,
+            name: tag_name,
+        })
+    }
+}
+
+/// 여러 좋아요를 한번에 추가, 삭제했을 때 하나하나의 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum LikeResult {
+    Created,
+    Deleted,
+    AlreadyExists,
+    NotFound,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+pub struct LikeState {
+    pub book_ids: Vec<u32>,
+    pub book_tags: Vec<Tag>,
+}
+
+impl LikeState {
+    pub fn is_book_liked(&self, book_id: u32) -> bool {
+        self.book_ids.contains(&book_id)
+    }
+
+    pub fn is_book_tag_liked(&self, tag: &Tag) -> bool {
+        self.book_tags.contains(tag)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Notification {
     Book {
+        /// 예전 알림에는 없음
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<Uuid>,
         book_id: u32,
-        book_tags: Vec<(String, String)>,
+        book_tags: Vec<Tag>,
+        /// 읽지 않았으면 `None`
+        #[serde(default)]
+        read_at: Option<DateTime<Utc>>,
         created_at: DateTime<Utc>,
     },
+    /// 새로 생긴 kind, 모르는 kind가 와도 역직렬화에 실패하지 않음
+    #[serde(other)]
+    #[cfg_attr(feature = "openapi", schemars(skip))]
+    Unknown,
+}
+
+impl Notification {
+    pub fn id(&self) -> Option<Uuid> {
+        match self {
+            Self::Book { id, .. } => *id,
+            Self::Unknown => None,
+        }
+    }
+
+    /// 모르는 알림은 읽은 것으로 봄
+    pub fn is_read(&self) -> bool {
+        match self {
+            Self::Book { read_at, .. } => read_at.is_some(),
+            Self::Unknown => true,
+        }
+    }
+
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Book { created_at, .. } => Some(*created_at),
+            Self::Unknown => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum History {
     Book {
         book_id: u32,
+        /// 마지막으로 읽은 페이지, 1부터 시작함
+        #[serde(default)]
+        page: usize,
+        /// 페이지 안에서 스크롤한 위치, `0.0..=1.0`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        scroll: Option<f32>,
+        #[serde(default)]
+        completed: bool,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     },
+    /// 새로 생긴 kind, 모르는 kind가 와도 역직렬화에 실패하지 않음
+    #[serde(other)]
+    #[cfg_attr(feature = "openapi", schemars(skip))]
+    Unknown,
+}
+
+impl History {
+    pub fn book_id(&self) -> Option<u32> {
+        match self {
+            Self::Book { book_id, .. } => Some(*book_id),
+            Self::Unknown => None,
+        }
+    }
+
+    pub fn page(&self) -> Option<usize> {
+        match self {
+            Self::Book { page, .. } => Some(*page),
+            Self::Unknown => None,
+        }
+    }
+
+    pub fn is_completed(&self) -> bool {
+        match self {
+            Self::Book { completed, .. } => *completed,
+            Self::Unknown => false,
+        }
+    }
+
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Book { created_at, .. } => Some(*created_at),
+            Self::Unknown => None,
+        }
+    }
+
+    pub fn updated_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Book { updated_at, .. } => Some(*updated_at),
+            Self::Unknown => None,
+        }
+    }
+}
+
+#[test]
+fn test_like_tag() {
+    let json = r#"{"kind":"book_tag","tag_kind":"parody","tag_name":"touhou","created_at":"2022-01-01T00:00:00Z"}"#;
+
+    let like: Like = serde_json::from_str(json).unwrap();
+    assert_eq!(like.tag(), Some(&Tag::from(("parody", "touhou"))));
+    assert_eq!(serde_json::to_string(&like).unwrap(), json);
+
+    let state = LikeState {
+        book_ids: vec![],
+        book_tags: vec![("female", "Big_Breasts").into()],
+    };
+    assert!(state.is_book_tag_liked(&"female:big_breasts".parse().unwrap()));
 }
@@ -1,23 +1,158 @@
 use serde::Serialize;
 
-#[derive(Debug, Serialize)]
+use crate::api::{library::model::Tag, UnknownKindError};
+
+use super::model;
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "openapi", schemars(rename = "LikePayload"))]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Like {
-    Book { book_id: u32 },
-    BookTag { tag_kind: String, tag_name: String },
+    Book {
+        book_id: u32,
+    },
+    BookTag {
+        #[serde(flatten, with = "model::flat_tag")]
+        #[cfg_attr(feature = "openapi", schemars(with = "model::FlatTag"))]
+        tag: Tag,
+    },
+}
+
+/// `get_my_likes`로 받은 좋아요를 `delete_like`에 넘길 수 있음
+impl TryFrom<model::Like> for Like {
+    type Error = UnknownKindError;
+
+    fn try_from(x: model::Like) -> Result<Self, Self::Error> {
+        match x {
+            model::Like::Book { book_id, .. } => Ok(Self::Book { book_id }),
+            model::Like::BookTag { tag, .. } => Ok(Self::BookTag { tag }),
+            model::Like::Unknown => Err(UnknownKindError),
+        }
+    }
+}
+
+impl TryFrom<&model::Like> for Like {
+    type Error = UnknownKindError;
+
+    fn try_from(x: &model::Like) -> Result<Self, Self::Error> {
+        x.clone().try_into()
+    }
+}
+
+impl From<Tag> for Like {
+    fn from(tag: Tag) -> Self {
+        Self::BookTag { tag }
+    }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum LikeKind {
     Book,
     BookTag,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 #[serde(rename_all = "kebab-case")]
 pub enum LikeSortBy {
     CreatedAtDesc,
     CreatedAtAsc,
     Random,
 }
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    Book,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum NotificationSortBy {
+    CreatedAtDesc,
+    CreatedAtAsc,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "openapi", schemars(rename = "HistoryPayload"))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum History {
+    Book {
+        book_id: u32,
+        /// 마지막으로 읽은 페이지, 1부터 시작함
+        page: usize,
+        /// 페이지 안에서 스크롤한 위치, `0.0..=1.0`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        scroll: Option<f32>,
+        completed: bool,
+    },
+}
+
+impl TryFrom<model::History> for History {
+    type Error = UnknownKindError;
+
+    fn try_from(x: model::History) -> Result<Self, Self::Error> {
+        match x {
+            model::History::Book {
+                book_id,
+                page,
+                scroll,
+                completed,
+                ..
+            } => Ok(Self::Book {
+                book_id,
+                page,
+                scroll,
+                completed,
+            }),
+            model::History::Unknown => Err(UnknownKindError),
+        }
+    }
+}
+
+impl History {
+    pub fn book(book_id: u32, page: usize) -> Self {
+        Self::Book {
+            book_id,
+            page,
+            scroll: None,
+            completed: false,
+        }
+    }
+
+    pub fn scroll(mut self, x: f32) -> Self {
+        let Self::Book { scroll, .. } = &mut self;
+        *scroll = Some(x);
+        self
+    }
+
+    pub fn completed(mut self, x: bool) -> Self {
+        let Self::Book { completed, .. } = &mut self;
+        *completed = x;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryKind {
+    Book,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum HistorySortBy {
+    CreatedAtDesc,
+    CreatedAtAsc,
+    UpdatedAtDesc,
+    UpdatedAtAsc,
+    Random,
+}
@@ -0,0 +1,542 @@
+//! 요청/응답을 파일(cassette)에 기록하고, CI에서 네트워크 없이 그대로 재생함
+//!
+//! ```ignore
+//! // 기록
+//! let cassette = Cassette::record("tests/cassettes/books_by_tags.json");
+//! cassette.run(async { /* sdk 호출 */ }).await;
+//! cassette.save()?;
+//!
+//! // 재생
+//! let cassette = Cassette::replay("tests/cassettes/books_by_tags.json")?;
+//! cassette.run(async { /* sdk 호출 */ }).await;
+//! ```
+//!
+//! 기록할 때 쿠키와 이메일 주소는 가려짐
+
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use http::header;
+use parking_lot::Mutex;
+use reqwest::{Client, Request, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::api::BaseError;
+
+const REDACTED: &str = "REDACTED";
+const REDACTED_EMAIL: &str = "redacted@example.com";
+
+tokio::task_local! {
+    static CASSETTE: Cassette;
+}
+
+/// `Cassette::run` 안에서 호출됐다면 현재 cassette를 반환함
+pub(crate) fn current() -> Option<Cassette> {
+    CASSETTE.try_with(Clone::clone).ok()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CassetteError {
+    #[error("Io: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Json: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// 재생할 때 기록된 요청과 비교할 항목
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub method: bool,
+    pub path: bool,
+    pub query: bool,
+    pub body: bool,
+}
+
+impl Default for Match {
+    fn default() -> Self {
+        Self {
+            method: true,
+            path: true,
+            query: true,
+            body: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Record,
+    Replay,
+}
+
+#[derive(Clone)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: Mode,
+    matching: Match,
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    interactions: Vec<Interaction>,
+    used: Vec<bool>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Tape {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub body: RecordedBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: RecordedBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "content", rename_all = "snake_case")]
+pub enum RecordedBody {
+    Empty,
+    Json(Value),
+    Text(String),
+    /// hex
+    Binary(String),
+}
+
+impl Cassette {
+    /// 실제 서버로 요청을 보내고, 요청/응답을 기록함
+    ///
+    /// 기록된 내용은 `save`를 호출해야 파일에 저장됨
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self::new(path.as_ref().to_path_buf(), Mode::Record, Vec::new())
+    }
+
+    /// 기록된 응답만으로 요청에 응답함
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, CassetteError> {
+        let path = path.as_ref().to_path_buf();
+        let tape: Tape = serde_json::from_slice(&std::fs::read(&path)?)?;
+
+        Ok(Self::new(path, Mode::Replay, tape.interactions))
+    }
+
+    fn new(path: PathBuf, mode: Mode, interactions: Vec<Interaction>) -> Self {
+        let used = vec![false; interactions.len()];
+
+        Self {
+            path,
+            mode,
+            matching: Match::default(),
+            inner: Arc::new(Mutex::new(Inner { interactions, used })),
+        }
+    }
+
+    pub fn matching(mut self, matching: Match) -> Self {
+        self.matching = matching;
+        self
+    }
+
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.inner.lock().interactions.clone()
+    }
+
+    /// future 안에서 보내는 sdk 요청은 모두 이 cassette를 거침
+    pub async fn run<F: Future>(&self, f: F) -> F::Output {
+        CASSETTE.scope(self.clone(), f).await
+    }
+
+    pub fn save(&self) -> Result<(), CassetteError> {
+        let tape = Tape {
+            interactions: self.interactions(),
+        };
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(&self.path, serde_json::to_vec_pretty(&tape)?)?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn send(&self, req: Request) -> Result<Response, BaseError> {
+        let recorded_request = recorded_request(&req);
+
+        match self.mode {
+            Mode::Record => {
+                let resp = Client::new()
+                    .execute(req)
+                    .await
+                    .map_err(BaseError::Reqwest)?;
+
+                let status = resp.status();
+                let headers = resp.headers().clone();
+                let body = resp.bytes().await.map_err(BaseError::BodyRead)?;
+
+                let recorded_response = RecordedResponse {
+                    status: status.as_u16(),
+                    headers: headers
+                        .iter()
+                        .map(|(name, value)| {
+                            let value = value.to_str().unwrap_or_default();
+
+                            (name.to_string(), redact_header(name, value))
+                        })
+                        .collect(),
+                    body: recorded_body(&headers, &body),
+                };
+
+                let mut inner = self.inner.lock();
+                inner.interactions.push(Interaction {
+                    request: recorded_request,
+                    response: recorded_response,
+                });
+                inner.used.push(true);
+
+                // 호출한 쪽에는 가려지지 않은 실제 응답을 돌려줌
+                let mut builder = http::Response::builder().status(status);
+                for (name, value) in headers.iter() {
+                    builder = builder.header(name, value);
+                }
+
+                builder
+                    .body(body)
+                    .map(Into::into)
+                    .map_err(|err| BaseError::Transport(err.to_string()))
+            }
+
+            Mode::Replay => {
+                let mut inner = self.inner.lock();
+                let Inner { interactions, used } = &mut *inner;
+
+                let found = interactions
+                    .iter()
+                    .zip(used.iter_mut())
+                    .find(|(x, used)| !**used && self.is_match(&x.request, &recorded_request));
+
+                let (interaction, used) = found.ok_or_else(|| {
+                    BaseError::Transport(format!(
+                        "no interaction in cassette `{}` for {} {}?{}",
+                        self.path.display(),
+                        recorded_request.method,
+                        recorded_request.path,
+                        recorded_request.query
+                    ))
+                })?;
+
+                *used = true;
+
+                let RecordedResponse {
+                    status,
+                    headers,
+                    body,
+                } = &interaction.response;
+
+                let mut builder = http::Response::builder().status(*status);
+                for (name, value) in headers {
+                    builder = builder.header(name.as_str(), value.as_str());
+                }
+
+                builder
+                    .body(body.to_bytes())
+                    .map(Into::into)
+                    .map_err(|err| BaseError::Transport(err.to_string()))
+            }
+        }
+    }
+
+    fn is_match(&self, recorded: &RecordedRequest, req: &RecordedRequest) -> bool {
+        let Match {
+            method,
+            path,
+            query,
+            body,
+        } = self.matching;
+
+        (!method || recorded.method == req.method)
+            && (!path || recorded.path == req.path)
+            && (!query || recorded.query == req.query)
+            && (!body || recorded.body == req.body)
+    }
+}
+
+fn recorded_request(req: &Request) -> RecordedRequest {
+    let url = req.url();
+    let body = req
+        .body()
+        .and_then(|x| x.as_bytes())
+        .map(|x| recorded_body(req.headers(), x))
+        .unwrap_or(RecordedBody::Empty);
+
+    RecordedRequest {
+        method: req.method().to_string(),
+        path: redact_emails(&percent_decode(url.path())),
+        query: redact_emails(&percent_decode(url.query().unwrap_or_default())),
+        body,
+    }
+}
+
+fn recorded_body(headers: &http::HeaderMap, body: &[u8]) -> RecordedBody {
+    if body.is_empty() {
+        return RecordedBody::Empty;
+    }
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|x| x.to_str().ok())
+        .unwrap_or_default();
+
+    if content_type.starts_with("application/json") {
+        if let Ok(mut value) = serde_json::from_slice::<Value>(body) {
+            redact_value(&mut value);
+            return RecordedBody::Json(value);
+        }
+    }
+
+    let is_text = content_type.starts_with("text/") || content_type.is_empty();
+
+    match std::str::from_utf8(body) {
+        Ok(text) if is_text => RecordedBody::Text(redact_emails(text)),
+        _ => RecordedBody::Binary(body.iter().map(|b| format!("{b:02x}")).collect()),
+    }
+}
+
+impl RecordedBody {
+    fn to_bytes(&self) -> Bytes {
+        match self {
+            Self::Empty => Bytes::new(),
+            Self::Json(value) => serde_json::to_vec(value).unwrap_or_default().into(),
+            Self::Text(text) => Bytes::from(text.clone()),
+            Self::Binary(hex) => (0..hex.len())
+                .step_by(2)
+                .filter_map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+                .collect::<Vec<_>>()
+                .into(),
+        }
+    }
+}
+
+/// set-cookie는 쿠키 이름만 남김
+fn redact_header(name: &header::HeaderName, value: &str) -> String {
+    if name == header::SET_COOKIE {
+        match value.split_once('=') {
+            Some((cookie, rest)) => {
+                let attributes = rest.split_once(';').map(|(_, x)| x).unwrap_or_default();
+
+                if attributes.is_empty() {
+                    format!("{cookie}={REDACTED}")
+                } else {
+                    format!("{cookie}={REDACTED};{attributes}")
+                }
+            }
+            None => REDACTED.to_string(),
+        }
+    } else {
+        redact_emails(value)
+    }
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::String(x) => *x = redact_emails(x),
+        Value::Array(xs) => xs.iter_mut().for_each(redact_value),
+        Value::Object(map) => map.values_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+fn redact_emails(x: &str) -> String {
+    let is_local = |c: char| c.is_ascii_alphanumeric() || "._%+-".contains(c);
+    let is_domain = |c: char| c.is_ascii_alphanumeric() || ".-".contains(c);
+
+    let mut redacted = String::with_capacity(x.len());
+    let mut rest = x;
+
+    while let Some(at) = rest.find('@') {
+        let (left, right) = (&rest[..at], &rest[at + 1..]);
+
+        let local = left.len()
+            - left
+                .chars()
+                .rev()
+                .take_while(|c| is_local(*c))
+                .map(char::len_utf8)
+                .sum::<usize>();
+        let domain = right
+            .char_indices()
+            .find(|(_, c)| !is_domain(*c))
+            .map(|(i, _)| i)
+            .unwrap_or(right.len());
+
+        let domain_part = right[..domain].trim_end_matches('.');
+
+        if local < left.len() && domain_part.contains('.') {
+            redacted.push_str(&left[..local]);
+            redacted.push_str(REDACTED_EMAIL);
+            rest = &right[domain_part.len()..];
+        } else {
+            redacted.push_str(&rest[..=at]);
+            rest = right;
+        }
+    }
+
+    redacted.push_str(rest);
+    redacted
+}
+
+fn percent_decode(x: &str) -> String {
+    let bytes = x.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = x
+            .get(i + 1..i + 3)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                decoded.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            redact_emails("/_/users/madome@madome.app"),
+            "/_/users/redacted@example.com"
+        );
+        assert_eq!(
+            redact_emails("a@b.com, c@d.co.kr."),
+            "redacted@example.com, redacted@example.com."
+        );
+        assert_eq!(redact_emails("@me and x@y"), "@me and x@y");
+
+        assert_eq!(
+            redact_header(
+                &header::SET_COOKIE,
+                "madome_access_token=abcd; Path=/; HttpOnly"
+            ),
+            "madome_access_token=REDACTED; Path=/; HttpOnly"
+        );
+
+        assert_eq!(
+            percent_decode("tags%5B0%5D%5B0%5D=a%40b.com"),
+            "tags[0][0]=a@b.com"
+        );
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        use chrono::Utc;
+
+        use crate::{api::library, mock::MockServer};
+
+        let path =
+            std::env::temp_dir().join(format!("madome-sdk-cassette-{}.json", std::process::id()));
+
+        let server = MockServer::start().await.unwrap();
+        let user_id = uuid::Uuid::from_u128(1);
+        let now = Utc::now();
+
+        server.seed_book(library::model::Book {
+            id: 1,
+            title: "title".to_string(),
+            tags: vec![("female", "loli").into()],
+            kind: library::model::BookKind::Doujinshi,
+            language: library::model::Language::Korean,
+            page: 2,
+            created_at: now,
+            updated_at: now,
+        });
+
+        let base_url = server.base_url();
+        let token = server.issue_token_pair(user_id);
+        let tags = vec![library::model::Tag::from(("female", "loli"))];
+
+        let cassette = Cassette::record(&path);
+        let recorded = cassette
+            .run(library::get_books_by_tags(
+                &base_url,
+                token.clone(),
+                tags.clone(),
+                25,
+                1,
+                None,
+            ))
+            .await
+            .unwrap();
+        cassette.save().unwrap();
+
+        drop(server);
+
+        let interactions = Cassette::replay(&path).unwrap().interactions();
+        assert_eq!(interactions.len(), 1);
+        assert!(interactions[0]
+            .request
+            .query
+            .contains("tags[0][0]=female&tags[0][1]=loli"));
+
+        let cassette = Cassette::replay(&path).unwrap();
+        let replayed = cassette
+            .run(library::get_books_by_tags(
+                &base_url,
+                token.clone(),
+                tags.clone(),
+                25,
+                1,
+                None,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(recorded.len(), replayed.len());
+
+        // 한 번 쓴 기록은 다시 쓰지 않음
+        let r = cassette
+            .run(library::get_books_by_tags(
+                &base_url, token, tags, 25, 1, None,
+            ))
+            .await;
+        assert!(matches!(
+            r,
+            Err(library::Error::Base(BaseError::Transport(_)))
+        ));
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -35,6 +35,17 @@ impl MadomeBaseUrl {
         }
     }
 
+    /// 모든 서비스가 하나의 base url을 씀
+    pub fn custom(base_url: impl Into<String>) -> Self {
+        let base_url = base_url.into();
+
+        Self {
+            auth: base_url.clone(),
+            user: base_url.clone(),
+            library: base_url,
+        }
+    }
+
     #[allow(dead_code)]
     pub fn internal() -> Self {
         Self {
@@ -0,0 +1,84 @@
+//! 푸시 알림을 받을 기기 등록
+
+use std::{fs, io, path::Path};
+
+use uuid::Uuid;
+
+use crate::api::user;
+
+use super::MadomeClient;
+
+/// `path`에 저장된 udid를 읽고, 없으면 새로 만들어서 저장함
+///
+/// 토큰을 저장하는 디렉토리에 두면 설치본마다 같은 udid를 씀
+pub fn load_or_create_udid(path: impl AsRef<Path>) -> io::Result<Uuid> {
+    let path = path.as_ref();
+
+    match fs::read_to_string(path) {
+        Ok(x) => x
+            .trim()
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            let udid = Uuid::new_v4();
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, udid.to_string())?;
+
+            Ok(udid)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeviceError {
+    #[error("udid is not set; use `udid_path` or `set_udid`")]
+    NoUdid,
+
+    #[error("{0}")]
+    User(#[from] user::Error),
+}
+
+impl MadomeClient {
+    /// `path`에 저장된 udid를 씀, 없으면 새로 만들어서 저장함
+    ///
+    /// ```ignore
+    /// let client = MadomeClient::stable().udid_path(data_dir.join("udid"))?;
+    /// ```
+    pub fn udid_path(self, path: impl AsRef<Path>) -> io::Result<Self> {
+        self.set_udid(load_or_create_udid(path)?);
+        Ok(self)
+    }
+
+    /// `udid_path`나 `set_udid`로 넣기 전에는 `None`
+    pub fn udid(&self) -> Option<Uuid> {
+        self.token.udid()
+    }
+
+    pub fn set_udid(&self, udid: Uuid) {
+        self.token.set_udid(udid)
+    }
+
+    /// 이 기기의 FCM 토큰을 등록함, 이미 등록된 기기면 토큰만 바꿈
+    pub async fn register_device(&self, fcm_token: impl Into<String>) -> Result<(), DeviceError> {
+        let udid = self.udid().ok_or(DeviceError::NoUdid)?;
+
+        self.user()
+            .create_or_update_fcm_token(udid, fcm_token)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 이 기기를 등록 해제함
+    pub async fn deregister_device(&self) -> Result<(), DeviceError> {
+        let udid = self.udid().ok_or(DeviceError::NoUdid)?;
+
+        self.user().delete_device(udid).await?;
+
+        Ok(())
+    }
+}
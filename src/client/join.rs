@@ -0,0 +1,170 @@
+//! 여러 namespace의 응답을 합쳐주는 helper
+
+use std::collections::HashMap;
+
+use futures_util::TryStreamExt;
+
+use crate::api::{library, user, DEFAULT_PER_PAGE};
+
+use super::MadomeClient;
+
+#[derive(Debug, thiserror::Error)]
+pub enum JoinError {
+    #[error("{0}")]
+    User(#[from] user::Error),
+
+    #[error("{0}")]
+    Library(#[from] library::Error),
+}
+
+/// 한번에 불러올 작품, 태그 수
+const CHUNK_SIZE: usize = 50;
+
+/// 좋아요한 작품, 태그를 library 객체로 바꾼 것, 좋아요 목록과 같은 순서
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedLikes {
+    /// 작품을 찾을 수 없는 좋아요는 빠짐
+    pub books: Vec<(user::model::Like, library::model::Book)>,
+    /// (좋아요, 태그의 작품)
+    pub book_tags: Vec<(user::model::Like, Vec<library::model::Book>)>,
+}
+
+/// 이어서 읽을 작품
+#[derive(Debug, Clone)]
+pub struct ContinueReading {
+    pub history: user::model::History,
+    pub book: library::model::Book,
+}
+
+impl ContinueReading {
+    /// 마지막으로 읽은 페이지
+    pub fn page(&self) -> usize {
+        self.history.page().unwrap_or_default()
+    }
+
+    /// `page / book.page`, `0.0..=1.0`
+    pub fn progress(&self) -> f32 {
+        if self.book.page == 0 {
+            return 0.0;
+        }
+
+        (self.page().min(self.book.page) as f32) / (self.book.page as f32)
+    }
+}
+
+impl MadomeClient {
+    /// 다 읽지 않은 작품을 최근에 읽은 순서로 가져옴
+    ///
+    /// 작품을 찾을 수 없는 기록은 건너뜀
+    pub async fn continue_reading(
+        &self,
+        per_page: usize,
+        page: usize,
+    ) -> Result<Vec<ContinueReading>, JoinError> {
+        let histories = self
+            .user()
+            .get_my_histories(per_page, page)
+            .kind(user::payload::HistoryKind::Book)
+            .sort_by(user::payload::HistorySortBy::UpdatedAtDesc)
+            .await?
+            .into_iter()
+            .filter(|x| !x.is_completed())
+            .collect::<Vec<_>>();
+
+        if histories.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids = histories
+            .iter()
+            .filter_map(|x| x.book_id())
+            .collect::<Vec<_>>();
+
+        let mut books = self
+            .library()
+            .get_books_by_ids(ids)
+            .await?
+            .into_iter()
+            .map(|x| (x.id, x))
+            .collect::<HashMap<_, _>>();
+
+        let r = histories
+            .into_iter()
+            .filter_map(|history| {
+                let book = books.remove(&history.book_id()?)?;
+                Some(ContinueReading { history, book })
+            })
+            .collect();
+
+        Ok(r)
+    }
+
+    /// 좋아요를 모두 불러와서 작품, 태그별 작품으로 바꿈
+    ///
+    /// 모르는 좋아요는 빠짐, 태그마다 `books_per_tag`개의 작품을 가져옴
+    pub async fn resolve_likes(&self, books_per_tag: usize) -> Result<ResolvedLikes, JoinError> {
+        let likes = self
+            .user()
+            .get_my_likes(DEFAULT_PER_PAGE, 1)
+            .sort_by(user::payload::LikeSortBy::CreatedAtDesc)
+            .stream()
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut book_likes = Vec::new();
+        let mut tag_likes = Vec::new();
+
+        for like in likes {
+            match like {
+                user::model::Like::Book { .. } => book_likes.push(like),
+                user::model::Like::BookTag { .. } => tag_likes.push(like),
+                // 모르는 좋아요는 library 객체로 바꿀 수 없음
+                user::model::Like::Unknown => {}
+            }
+        }
+
+        let mut books = HashMap::new();
+        let book_ids = book_likes.iter().filter_map(|x| match x {
+            user::model::Like::Book { book_id, .. } => Some(*book_id),
+            _ => None,
+        });
+        for chunk in book_ids.collect::<Vec<_>>().chunks(CHUNK_SIZE) {
+            let xs = self.library().get_books_by_ids(chunk.to_vec()).await?;
+            books.extend(xs.into_iter().map(|x| (x.id, x)));
+        }
+
+        let mut tag_books = HashMap::new();
+        let tags = tag_likes.iter().filter_map(|x| x.tag().cloned());
+        for chunk in tags.collect::<Vec<_>>().chunks(CHUNK_SIZE) {
+            let xs = self
+                .library()
+                .get_books_by_tags(chunk.to_vec(), books_per_tag, 1)
+                .await?;
+            tag_books.extend(xs);
+        }
+
+        let books = book_likes
+            .into_iter()
+            .filter_map(|like| match &like {
+                user::model::Like::Book { book_id, .. } => {
+                    let book = books.get(book_id)?.clone();
+                    Some((like, book))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let book_tags = tag_likes
+            .into_iter()
+            .map(|like| {
+                let xs = like
+                    .tag()
+                    .and_then(|tag| tag_books.get(tag).cloned())
+                    .unwrap_or_default();
+                (like, xs)
+            })
+            .collect();
+
+        Ok(ResolvedLikes { books, book_tags })
+    }
+}
@@ -1,11 +1,17 @@
 pub mod base_url;
+pub mod device;
+pub mod join;
+pub mod notification;
+pub mod session;
 pub mod store;
 
+use std::sync::Arc;
+
 use self::{base_url::MadomeBaseUrl, store::AuthStore};
 
 pub struct MadomeClient {
     base_url: MadomeBaseUrl,
-    token: AuthStore,
+    token: Arc<AuthStore>,
 }
 
 impl MadomeClient {
@@ -29,6 +35,13 @@ impl MadomeClient {
             token: Default::default(),
         }
     }
+
+    pub fn custom(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: MadomeBaseUrl::custom(base_url),
+            token: Default::default(),
+        }
+    }
 }
 
 macro_rules! impl_madome_client {
@@ -38,7 +51,7 @@ macro_rules! impl_madome_client {
                 pub fn $namespace(&self) -> $namespace {
                     $namespace {
                         base_url: self.base_url.$namespace.as_str(),
-                        token: &self.token
+                        token: self.token.as_ref()
                     }
                 }
             )*
@@ -58,30 +71,68 @@ impl_madome_client![user, auth, library];
 
 #[cfg(feature = "e2e")]
 pub mod tests {
-    use std::{convert::Infallible, fmt::Debug, net::SocketAddr, sync::Arc, time::Duration};
+    use std::{
+        convert::Infallible,
+        net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener},
+        sync::Arc,
+        time::Duration,
+    };
 
     use http::{Request, Response, StatusCode};
     use hyper::{
-        body::HttpBody,
         service::{make_service_fn, service_fn},
         Body, Server,
     };
+    use parking_lot::Mutex;
     use serde::{de::DeserializeOwned, Deserialize, Serialize};
-    use tokio::sync::{oneshot, Mutex};
+    use tokio::sync::oneshot;
+    use uuid::Uuid;
 
     use super::MadomeClient;
+    use crate::api::{library, user};
+
+    const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 
     impl MadomeClient {
         pub fn e2e_channel(&self, base_url: impl Into<String>) -> e2e_channel {
             e2e_channel {
                 base_url: base_url.into(),
+                bind_addr: IpAddr::V4(Ipv4Addr::LOCALHOST),
+                timeout: DEFAULT_TIMEOUT,
             }
         }
     }
 
+    #[derive(Debug, thiserror::Error)]
+    pub enum E2eError {
+        #[error("Io: {0}")]
+        Io(#[from] std::io::Error),
+
+        #[error("Hyper: {0}")]
+        Hyper(#[from] hyper::Error),
+
+        #[error("Reqwest: {0}")]
+        Reqwest(#[from] reqwest::Error),
+
+        #[error("Json Serialize: {0}")]
+        JsonSerialize(#[from] serde_json::Error),
+
+        #[error("e2e-channel is not normal: {0} {1}")]
+        Status(StatusCode, String),
+
+        #[error("Timeout: no response from e2e-channel in {0:?}")]
+        Timeout(Duration),
+
+        #[error("Receiver closed")]
+        Closed,
+    }
+
     #[allow(non_camel_case_types)]
     pub struct e2e_channel {
         pub base_url: String,
+        /// e2e-channel의 응답을 받을 receiver의 주소, port는 OS가 정함
+        pub bind_addr: IpAddr,
+        pub timeout: Duration,
     }
 
     #[derive(Debug, Deserialize)]
@@ -89,119 +140,265 @@ pub mod tests {
         pub code: String,
     }
 
-    #[derive(Serialize)]
+    #[derive(Debug, Serialize)]
     #[serde(tag = "kind", rename_all = "snake_case")]
-    enum E2eChannelCommand {
-        Authcode { email: String, port: u16 },
+    pub enum E2eChannelCommand {
+        /// 인증코드를 `port`의 receiver로 보내줌
+        Authcode {
+            email: String,
+            port: u16,
+        },
+        SeedUser {
+            user: user::model::User,
+        },
+        SeedBook {
+            book: library::model::Book,
+        },
+        SeedLike {
+            user_id: Uuid,
+            like: user::model::Like,
+        },
+        Reset,
     }
 
     impl E2eChannelCommand {
-        pub fn serialize(&self) -> Vec<u8> {
-            serde_json::to_vec(self).unwrap()
+        pub fn serialize(&self) -> Result<Vec<u8>, E2eError> {
+            Ok(serde_json::to_vec(self)?)
         }
     }
 
     impl e2e_channel {
-        pub async fn authcode(&self, email: impl Into<String>) -> Authcode {
-            let port = available_port();
-
-            let (tx, rx) = oneshot::channel::<Authcode>();
-
-            tokio::spawn(async move {
-                open_receiver(tx, port).await;
-            });
+        /// `0.0.0.0`처럼 다른 host에서 접근할 수 있는 주소가 필요할 때 씀
+        pub fn bind_addr(mut self, bind_addr: IpAddr) -> Self {
+            self.bind_addr = bind_addr;
+            self
+        }
 
-            let command = E2eChannelCommand::Authcode {
-                email: email.into(),
-                port,
-            };
+        pub fn timeout(mut self, timeout: Duration) -> Self {
+            self.timeout = timeout;
+            self
+        }
 
+        pub async fn send(&self, command: &E2eChannelCommand) -> Result<(), E2eError> {
             let resp = reqwest::Client::new()
                 .post(&self.base_url)
-                .body(command.serialize())
+                .body(command.serialize()?)
+                .timeout(self.timeout)
                 .send()
-                .await
-                .unwrap();
-
-            if resp.status() != StatusCode::NO_CONTENT {
-                panic!("e2e-channel is not normal")
+                .await?;
+
+            match resp.status() {
+                StatusCode::NO_CONTENT => Ok(()),
+                code => Err(E2eError::Status(
+                    code,
+                    resp.text().await.unwrap_or_default(),
+                )),
             }
+        }
+
+        /// receiver를 열고 그 port로 command를 보낸 뒤, receiver로 오는 응답을 기다림
+        pub async fn request<T>(
+            &self,
+            command: impl FnOnce(u16) -> E2eChannelCommand,
+        ) -> Result<T, E2eError>
+        where
+            T: DeserializeOwned + Send + 'static,
+        {
+            let receiver = Receiver::<T>::bind(self.bind_addr, self.timeout)?;
 
-            let authcode = rx.await.unwrap();
+            self.send(&command(receiver.port())).await?;
 
-            authcode
+            receiver.recv().await
         }
-    }
 
-    pub fn available_port() -> u16 {
-        for port in [31111, 31112, 31113, 31114].into_iter().cycle() {
-            let listener = std::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port)));
+        pub async fn authcode(&self, email: impl Into<String>) -> Result<Authcode, E2eError> {
+            let email = email.into();
 
-            if let Ok(_listener) = listener {
-                return port;
-            }
+            self.request(|port| E2eChannelCommand::Authcode { email, port })
+                .await
+        }
 
-            std::thread::sleep(Duration::from_millis(500));
+        pub async fn seed_user(&self, user: user::model::User) -> Result<(), E2eError> {
+            self.send(&E2eChannelCommand::SeedUser { user }).await
         }
 
-        unreachable!()
+        pub async fn seed_book(&self, book: library::model::Book) -> Result<(), E2eError> {
+            self.send(&E2eChannelCommand::SeedBook { book }).await
+        }
+
+        pub async fn seed_like(
+            &self,
+            user_id: Uuid,
+            like: user::model::Like,
+        ) -> Result<(), E2eError> {
+            self.send(&E2eChannelCommand::SeedLike { user_id, like })
+                .await
+        }
+
+        pub async fn reset(&self) -> Result<(), E2eError> {
+            self.send(&E2eChannelCommand::Reset).await
+        }
+    }
+
+    /// json body 하나를 받을 때까지 열려있는 http 서버
+    pub struct Receiver<T> {
+        addr: SocketAddr,
+        rx: oneshot::Receiver<T>,
+        timeout: Duration,
+        shutdown: Option<oneshot::Sender<()>>,
     }
 
-    async fn open_receiver<T>(json_tx: oneshot::Sender<T>, port: u16)
+    impl<T> Receiver<T>
     where
-        T: Debug + DeserializeOwned + Send + Sync + 'static,
+        T: DeserializeOwned + Send + 'static,
     {
-        let (close_tx, close_rx) = oneshot::channel::<()>();
+        pub fn bind(ip: IpAddr, timeout: Duration) -> Result<Self, E2eError> {
+            let listener = TcpListener::bind(SocketAddr::new(ip, 0))?;
+            listener.set_nonblocking(true)?;
+            let addr = listener.local_addr()?;
 
-        let close_tx = Arc::new(Mutex::new(Some(close_tx)));
-        let json_tx = Arc::new(Mutex::new(Some(json_tx)));
+            let (json_tx, rx) = oneshot::channel::<T>();
+            let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
 
-        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+            let json_tx = Arc::new(Mutex::new(Some(json_tx)));
 
-        let svc = |close_tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
-                   json_tx: Arc<Mutex<Option<oneshot::Sender<T>>>>| async move {
-            Ok::<_, Infallible>(service_fn(move |request| {
-                handle_request(close_tx.clone(), json_tx.clone(), request)
-            }))
-        };
+            let server = Server::from_tcp(listener)?.serve(make_service_fn(move |_| {
+                let json_tx = json_tx.clone();
 
-        let server = hyper::Server::bind(&addr).serve(make_service_fn(move |_| {
-            svc(close_tx.clone(), json_tx.clone())
-        }));
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request| {
+                        handle_request(json_tx.clone(), request)
+                    }))
+                }
+            }));
+
+            tokio::spawn(server.with_graceful_shutdown(async {
+                shutdown_rx.await.ok();
+            }));
+
+            Ok(Self {
+                addr,
+                rx,
+                timeout,
+                shutdown: Some(shutdown_tx),
+            })
+        }
 
-        let server = Server::with_graceful_shutdown(server, async {
-            close_rx.await.ok();
-        });
+        pub fn port(&self) -> u16 {
+            self.addr.port()
+        }
 
-        server.await.ok();
+        pub async fn recv(mut self) -> Result<T, E2eError> {
+            match tokio::time::timeout(self.timeout, &mut self.rx).await {
+                Ok(Ok(x)) => Ok(x),
+                Ok(Err(_)) => Err(E2eError::Closed),
+                Err(_) => Err(E2eError::Timeout(self.timeout)),
+            }
+        }
+    }
+
+    impl<T> Drop for Receiver<T> {
+        fn drop(&mut self) {
+            if let Some(shutdown) = self.shutdown.take() {
+                shutdown.send(()).ok();
+            }
+        }
     }
 
     async fn handle_request<T>(
-        close_tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
         json_tx: Arc<Mutex<Option<oneshot::Sender<T>>>>,
-        mut request: Request<Body>,
+        request: Request<Body>,
     ) -> Result<Response<Body>, Infallible>
     where
-        T: Debug + DeserializeOwned + Send + Sync,
+        T: DeserializeOwned,
     {
-        let mut close_tx = close_tx.lock().await;
-        close_tx.take().unwrap().send(()).ok();
+        let status = |status: StatusCode, body: String| {
+            let mut resp = Response::new(Body::from(body));
+            *resp.status_mut() = status;
+            resp
+        };
 
-        let body = request.body_mut();
+        let buf = match hyper::body::to_bytes(request.into_body()).await {
+            Ok(x) => x,
+            Err(err) => return Ok(status(StatusCode::BAD_REQUEST, err.to_string())),
+        };
 
-        let mut buf = Vec::new();
+        // 잘못된 body는 거절하고 올바른 body를 계속 기다림
+        let json: T = match serde_json::from_slice(&buf) {
+            Ok(x) => x,
+            Err(err) => {
+                log::warn!("e2e receiver: {err}");
+                return Ok(status(StatusCode::BAD_REQUEST, err.to_string()));
+            }
+        };
 
-        while let Some(Ok(a)) = body.data().await {
-            buf.push(a)
+        match json_tx.lock().take() {
+            Some(json_tx) => {
+                json_tx.send(json).ok();
+                Ok(Response::new(Body::empty()))
+            }
+            None => Ok(status(StatusCode::GONE, "already received".to_string())),
         }
+    }
+
+    /// authcode를 받으면 receiver로 `{"code": "123456"}`을 보내는 e2e-channel
+    #[cfg(test)]
+    async fn fake_e2e_channel() -> SocketAddr {
+        async fn handle(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+            let buf = hyper::body::to_bytes(request.into_body()).await.unwrap();
+            let command: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+
+            if command["kind"] == "authcode" {
+                let port = command["port"].as_u64().unwrap();
+                let url = format!("http://127.0.0.1:{port}");
+
+                tokio::spawn(async move {
+                    let client = reqwest::Client::new();
+
+                    let resp = client.post(&url).body("not json").send().await.unwrap();
+                    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+                    client
+                        .post(&url)
+                        .body(r#"{"code":"123456"}"#)
+                        .send()
+                        .await
+                        .ok();
+                });
+            }
+
+            let mut resp = Response::new(Body::empty());
+            *resp.status_mut() = StatusCode::NO_CONTENT;
+            Ok(resp)
+        }
+
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service_fn(
+            |_| async { Ok::<_, Infallible>(service_fn(handle)) },
+        ));
+        let addr = server.local_addr();
+
+        tokio::spawn(server);
+
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_e2e_channel() {
+        let addr = fake_e2e_channel().await;
 
-        let buf = buf.concat();
+        let channel = MadomeClient::custom("")
+            .e2e_channel(format!("http://{addr}"))
+            .timeout(Duration::from_millis(500));
 
-        let json: T = serde_json::from_slice(&buf).expect("json serialize");
+        let authcode = channel.authcode("madome@madome.app").await.unwrap();
+        assert_eq!(authcode.code, "123456");
 
-        let mut json_tx = json_tx.lock().await;
-        json_tx.take().unwrap().send(json).ok();
+        channel.reset().await.unwrap();
 
-        Ok::<_, Infallible>(Response::new(Body::empty()))
+        // 응답을 보내지 않는 command
+        let r = channel
+            .request::<Authcode>(|_| E2eChannelCommand::Reset)
+            .await;
+        assert!(matches!(r, Err(E2eError::Timeout(_))));
     }
 }
@@ -0,0 +1,328 @@
+//! FCM을 받을 수 없는 클라이언트를 위한 알림 polling
+
+use std::{
+    cmp::Ordering,
+    collections::{HashSet, VecDeque},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use futures_core::{future::BoxFuture, Stream};
+use futures_util::TryStreamExt;
+use tokio::time::{sleep, Sleep};
+use uuid::Uuid;
+
+use crate::api::{user, DEFAULT_PER_PAGE};
+
+use super::MadomeClient;
+
+pub struct Notifications<'a> {
+    client: &'a MadomeClient,
+}
+
+impl MadomeClient {
+    pub fn notifications(&self) -> Notifications<'_> {
+        Notifications { client: self }
+    }
+}
+
+impl<'a> Notifications<'a> {
+    /// `interval`마다 최근 알림을 불러와서 처음 보는 알림만 오래된 순서로 내보냄
+    ///
+    /// 이미 본 알림이 나오거나 빈 페이지가 나올 때까지 다음 페이지를 불러오므로 사이에 온 알림을 놓치지 않음
+    ///
+    /// 본 알림 중 가장 최근 `created_at`보다 오래된 알림은 본 것으로 보므로, 늦게 생긴 오래된 알림은 내보내지 않음
+    ///
+    /// id가 없는 모르는 알림은 내보내지 않음
+    ///
+    /// 처음 불러온 알림은 기준으로만 쓰고 내보내지 않음, 에러가 나도 끝나지 않고 다음 `interval`에 다시 불러옴
+    ///
+    /// ```ignore
+    /// let mut watch = client.notifications().watch(Duration::from_secs(30));
+    ///
+    /// while let Some(notification) = watch.next().await {
+    ///     println!("{:?}", notification?);
+    /// }
+    /// ```
+    pub fn watch(self, interval: Duration) -> NotificationWatch<'a> {
+        NotificationWatch {
+            client: self.client,
+            interval,
+            seen: None,
+            buffer: VecDeque::new(),
+            pending: None,
+            sleep: None,
+        }
+    }
+
+    pub async fn unread_count(self) -> Result<usize, user::Error> {
+        self.client.user().get_unread_notification_count().await
+    }
+
+    pub async fn read(self, notification_id: Uuid) -> Result<(), user::Error> {
+        self.client.user().read_notification(notification_id).await
+    }
+
+    pub async fn read_all(self) -> Result<(), user::Error> {
+        self.client.user().read_all_notifications().await
+    }
+}
+
+type FetchNotifications<'a> = BoxFuture<'a, Result<Vec<user::model::Notification>, user::Error>>;
+
+pub struct NotificationWatch<'a> {
+    client: &'a MadomeClient,
+    interval: Duration,
+    /// 처음 불러오기 전에는 `None`
+    seen: Option<Seen>,
+    buffer: VecDeque<user::model::Notification>,
+    pending: Option<FetchNotifications<'a>>,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+// pin projection을 하지 않으므로 안전함
+impl Unpin for NotificationWatch<'_> {}
+
+impl NotificationWatch<'_> {
+    fn fetch(&mut self) {
+        let client = self.client;
+        let seen = self.seen.clone();
+
+        let fut = async move {
+            let paginator = client
+                .user()
+                .get_notifications(DEFAULT_PER_PAGE, 1)
+                .sort_by(user::payload::NotificationSortBy::CreatedAtDesc)
+                .paginate();
+
+            // 처음에는 기준만 잡으면 되므로 첫 페이지만 불러옴
+            let mut stream = match seen {
+                Some(_) => paginator.stream(),
+                None => paginator.max_pages(1).stream(),
+            };
+
+            let mut notifications = Vec::new();
+
+            while let Some(x) = stream.try_next().await? {
+                let done = seen.as_ref().is_some_and(|seen| seen.contains(&x));
+
+                notifications.push(x);
+
+                if done {
+                    break;
+                }
+            }
+
+            Ok(notifications)
+        };
+
+        self.pending = Some(Box::pin(fut));
+    }
+
+    fn receive(&mut self, notifications: Vec<user::model::Notification>) {
+        let first = self.seen.is_none();
+        let seen = self.seen.get_or_insert_with(Seen::default);
+
+        // 최신순으로 오므로 뒤집어서 오래된 것부터 내보냄
+        for notification in notifications.into_iter().rev() {
+            if notification.id().is_some() && seen.insert(&notification) && !first {
+                self.buffer.push_back(notification);
+            }
+        }
+    }
+}
+
+/// 이미 본 알림 중 가장 최근 `created_at`과 그 시각에 만들어진 알림의 id
+///
+/// 본 알림을 모두 들고 있지 않아도 되도록 가장 최근 시각의 id만 남김
+#[derive(Debug, Clone, Default)]
+struct Seen {
+    created_at: Option<DateTime<Utc>>,
+    ids: HashSet<Uuid>,
+}
+
+impl Seen {
+    /// 이미 봤거나 본 알림보다 오래된 알림
+    fn contains(&self, notification: &user::model::Notification) -> bool {
+        let (Some(latest), Some(created_at)) = (self.created_at, notification.created_at()) else {
+            return false;
+        };
+
+        match created_at.cmp(&latest) {
+            Ordering::Less => true,
+            Ordering::Equal => notification.id().is_none_or(|id| self.ids.contains(&id)),
+            Ordering::Greater => false,
+        }
+    }
+
+    /// 처음 보는 알림이면 `true`, 오래된 것부터 넣어야 함
+    fn insert(&mut self, notification: &user::model::Notification) -> bool {
+        let (Some(id), Some(created_at)) = (notification.id(), notification.created_at()) else {
+            return false;
+        };
+
+        if self.contains(notification) {
+            return false;
+        }
+
+        if self.created_at != Some(created_at) {
+            self.created_at = Some(created_at);
+            self.ids.clear();
+        }
+
+        self.ids.insert(id)
+    }
+}
+
+impl Stream for NotificationWatch<'_> {
+    type Item = Result<user::model::Notification, user::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(x) = this.buffer.pop_front() {
+                return Poll::Ready(Some(Ok(x)));
+            }
+
+            if let Some(pending) = this.pending.as_mut() {
+                let r = match pending.as_mut().poll(cx) {
+                    Poll::Ready(r) => r,
+                    Poll::Pending => return Poll::Pending,
+                };
+
+                this.pending = None;
+                this.sleep = Some(Box::pin(sleep(this.interval)));
+
+                match r {
+                    Ok(notifications) => {
+                        this.receive(notifications);
+                        continue;
+                    }
+                    Err(err) => return Poll::Ready(Some(Err(err))),
+                }
+            }
+
+            if let Some(sleep) = this.sleep.as_mut() {
+                if sleep.as_mut().poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+
+            this.sleep = None;
+            this.fetch();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(id: u128, created_at: i64) -> user::model::Notification {
+        user::model::Notification::Book {
+            id: Some(Uuid::from_u128(id)),
+            book_id: id as u32,
+            book_tags: vec![],
+            read_at: None,
+            created_at: DateTime::from_timestamp(created_at, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_seen() {
+        let mut seen = Seen::default();
+
+        assert!(seen.insert(&notification(1, 10)));
+        assert!(seen.insert(&notification(2, 20)));
+        assert!(seen.insert(&notification(3, 20)));
+        assert!(!seen.insert(&notification(2, 20)));
+
+        // 가장 최근 시각의 id만 들고 있음
+        assert_eq!(seen.ids.len(), 2);
+        assert!(seen.contains(&notification(1, 10)));
+        assert!(seen.contains(&notification(4, 15)));
+        assert!(!seen.contains(&notification(4, 20)));
+        assert!(!seen.contains(&user::model::Notification::Unknown));
+
+        assert!(seen.insert(&notification(4, 30)));
+        assert_eq!(seen.ids.len(), 1);
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test(start_paused = true)]
+    async fn test_notifications() {
+        use futures_util::StreamExt;
+
+        use crate::mock::{fixtures::login, MockServer};
+
+        /// watch가 한번 더 불러와서 확인할 때까지 아무것도 내보내지 않음
+        async fn assert_quiet(server: &MockServer, watch: &mut NotificationWatch<'_>) {
+            let n = server.calls("user", "get_notifications");
+
+            // n + 1번째 응답을 받은 뒤에야 n + 2번째 요청을 보냄
+            tokio::select! {
+                x = watch.next() => panic!("unexpected notification: {x:?}"),
+                _ = server.wait_responses("user", "get_notifications", n + 2) => {}
+            }
+        }
+
+        let server = MockServer::start().await.unwrap();
+        let client = login(&server).await;
+        let user_id = Uuid::from_u128(1);
+        let now = Utc::now().timestamp();
+
+        let notification = |id: u128| notification(id, now + id as i64);
+
+        server.seed_notification(user_id, notification(1));
+
+        let mut watch = client.notifications().watch(Duration::from_millis(50));
+
+        // 처음 불러온 알림은 내보내지 않음
+        assert_quiet(&server, &mut watch).await;
+
+        server.seed_notification(user_id, notification(2));
+
+        let x = watch.next().await.unwrap().unwrap();
+        assert_eq!(x.id(), Some(Uuid::from_u128(2)));
+
+        assert_eq!(client.notifications().unread_count().await.unwrap(), 2);
+
+        client
+            .notifications()
+            .read(Uuid::from_u128(1))
+            .await
+            .unwrap();
+        assert_eq!(client.notifications().unread_count().await.unwrap(), 1);
+        assert!(matches!(
+            client.notifications().read(Uuid::from_u128(3)).await,
+            Err(user::Error::ReadNotification(
+                user::read_notification::Error::NotFoundNotification
+            ))
+        ));
+
+        // 한 페이지보다 많은 알림이 한번에 와도 놓치지 않음
+        let per_page = DEFAULT_PER_PAGE as u128;
+        for id in 10..10 + per_page * 2 + 5 {
+            server.seed_notification(user_id, notification(id));
+        }
+
+        for id in 10..10 + per_page * 2 + 5 {
+            let x = watch.next().await.unwrap().unwrap();
+            assert_eq!(x.id(), Some(Uuid::from_u128(id)));
+        }
+        assert_quiet(&server, &mut watch).await;
+
+        client.notifications().read_all().await.unwrap();
+        let unread = client
+            .user()
+            .get_notifications(25, 1)
+            .unread(true)
+            .await
+            .unwrap();
+        assert!(unread.is_empty());
+    }
+}
@@ -0,0 +1,405 @@
+//! 읽는 중인 페이지를 모아서 history로 보내주는 helper
+
+use std::{sync::Arc, time::Duration};
+
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::{sleep_until, Instant},
+};
+
+use crate::api::{user, BaseError, TokenBehavior};
+
+use super::{store::AuthStore, MadomeClient};
+
+const DEFAULT_DEBOUNCE: Duration = Duration::from_secs(3);
+const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_MAX_RETRIES: usize = 5;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    #[error("no page to complete; call `page` or `scroll` before `close(true)`")]
+    NoPage,
+
+    #[error("{0}")]
+    User(#[from] user::Error),
+}
+
+enum Event {
+    Page {
+        page: usize,
+        scroll: Option<f32>,
+    },
+    Flush(Option<oneshot::Sender<Result<(), user::Error>>>),
+    Close {
+        completed: bool,
+        tx: oneshot::Sender<Result<(), user::Error>>,
+    },
+}
+
+#[derive(Clone)]
+struct Options {
+    debounce: Duration,
+    retry_interval: Duration,
+    max_retries: usize,
+}
+
+/// 한 작품을 읽는 동안의 페이지 이동을 `debounce`만큼 모아서 `create_or_update_history`로 보냄
+///
+/// 실패한 요청은 background에서 `retry_interval`부터 두배씩 늘려가며 `max_retries`번 다시 보냄
+///
+/// drop하면 모아둔 페이지를 background에서 마저 보냄, tokio runtime 안에서 써야함
+///
+/// ```ignore
+/// let mut session = client.reading_session(book_id).debounce(Duration::from_secs(5));
+///
+/// session.page(1);
+/// session.page(2);
+///
+/// session.pause().await?;
+/// session.close(true).await?;
+/// ```
+pub struct ReadingSession {
+    base_url: String,
+    token: Arc<AuthStore>,
+    book_id: u32,
+    options: Options,
+    /// 한번이라도 페이지를 보냈는지
+    paged: bool,
+    tx: Option<mpsc::UnboundedSender<Event>>,
+}
+
+impl MadomeClient {
+    pub fn reading_session(&self, book_id: u32) -> ReadingSession {
+        ReadingSession {
+            base_url: self.base_url.user.clone(),
+            token: self.token.clone(),
+            book_id,
+            options: Options {
+                debounce: DEFAULT_DEBOUNCE,
+                retry_interval: DEFAULT_RETRY_INTERVAL,
+                max_retries: DEFAULT_MAX_RETRIES,
+            },
+            paged: false,
+            tx: None,
+        }
+    }
+}
+
+impl ReadingSession {
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.options.debounce = debounce;
+        self
+    }
+
+    pub fn retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.options.retry_interval = retry_interval;
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.options.max_retries = max_retries;
+        self
+    }
+
+    pub fn book_id(&self) -> u32 {
+        self.book_id
+    }
+
+    /// 현재 페이지, 1부터 시작함
+    pub fn page(&mut self, page: usize) {
+        self.paged = true;
+        self.send(Event::Page { page, scroll: None });
+    }
+
+    /// 현재 페이지와 페이지 안에서 스크롤한 위치, `0.0..=1.0`
+    pub fn scroll(&mut self, page: usize, scroll: f32) {
+        self.paged = true;
+        self.send(Event::Page {
+            page,
+            scroll: Some(scroll),
+        });
+    }
+
+    /// 기다리지 않고 바로 보냄
+    ///
+    /// 보낼 것이 없으면 `Ok(())`
+    pub async fn pause(&mut self) -> Result<(), user::Error> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Event::Flush(Some(tx)));
+
+        rx.await.unwrap_or(Ok(()))
+    }
+
+    /// 마지막 페이지를 보내고 세션을 끝냄
+    ///
+    /// 보내지 못했으면 에러를 돌려주고, background에서 계속 다시 보냄
+    ///
+    /// 완독으로 끝내려면 먼저 페이지를 보내야함, 아니면 `SessionError::NoPage`
+    pub async fn close(mut self, completed: bool) -> Result<(), SessionError> {
+        if completed && !self.paged {
+            return Err(SessionError::NoPage);
+        }
+
+        let (tx, rx) = oneshot::channel();
+        self.send(Event::Close { completed, tx });
+        self.tx.take();
+
+        Ok(rx.await.unwrap_or(Ok(()))?)
+    }
+
+    fn send(&mut self, event: Event) {
+        let tx = self.tx.get_or_insert_with(|| {
+            let (tx, rx) = mpsc::unbounded_channel();
+
+            let worker = Worker {
+                base_url: self.base_url.clone(),
+                token: self.token.clone(),
+                book_id: self.book_id,
+                options: self.options.clone(),
+                pending: None,
+                last: None,
+                completed: false,
+                retries: 0,
+            };
+            tokio::spawn(worker.run(rx));
+
+            tx
+        });
+
+        tx.send(event).ok();
+    }
+}
+
+struct Worker {
+    base_url: String,
+    token: Arc<AuthStore>,
+    book_id: u32,
+    options: Options,
+    /// 아직 보내지 않은 (page, scroll)
+    pending: Option<(usize, Option<f32>)>,
+    last: Option<(usize, Option<f32>)>,
+    completed: bool,
+    retries: usize,
+}
+
+impl Worker {
+    async fn run(mut self, mut rx: mpsc::UnboundedReceiver<Event>) {
+        let mut deadline = None;
+
+        loop {
+            let event = match deadline {
+                Some(at) => tokio::select! {
+                    event = rx.recv() => event,
+                    _ = sleep_until(at) => Some(Event::Flush(None)),
+                },
+                None => rx.recv().await,
+            };
+
+            let (reply, closed) = match event {
+                Some(Event::Page { page, scroll }) => {
+                    self.pending = Some((page, scroll));
+                    self.last = self.pending;
+                    self.retries = 0;
+                    deadline = Some(Instant::now() + self.options.debounce);
+                    continue;
+                }
+                Some(Event::Flush(reply)) => (reply, false),
+                Some(Event::Close { completed, tx }) => {
+                    if completed {
+                        self.completed = true;
+                        self.pending = self.last;
+                    }
+                    (Some(tx), true)
+                }
+                None => (None, true),
+            };
+
+            let r = self.flush().await;
+            deadline = self.next_retry(&r);
+
+            if let Some(reply) = reply {
+                reply.send(r).ok();
+            }
+
+            // 닫힌 뒤에는 남은 재시도만 마저 함
+            if closed {
+                while let Some(at) = deadline {
+                    sleep_until(at).await;
+
+                    let r = self.flush().await;
+                    deadline = self.next_retry(&r);
+                }
+
+                return;
+            }
+        }
+    }
+
+    /// 다시 보낼 시간, 보냈거나 포기했으면 `None`
+    fn next_retry(&mut self, r: &Result<(), user::Error>) -> Option<Instant> {
+        let Err(err) = r else {
+            self.retries = 0;
+            return None;
+        };
+
+        if !is_retriable(err) || self.retries >= self.options.max_retries {
+            log::warn!("reading session: give up book_id = {}; {err}", self.book_id);
+
+            self.pending = None;
+            self.retries = 0;
+            return None;
+        }
+
+        let interval = backoff(self.options.retry_interval, self.retries);
+        self.retries += 1;
+
+        Some(Instant::now() + interval)
+    }
+
+    async fn flush(&mut self) -> Result<(), user::Error> {
+        let Some((page, scroll)) = self.pending else {
+            return Ok(());
+        };
+
+        let mut history =
+            user::payload::History::book(self.book_id, page).completed(self.completed);
+        if let Some(scroll) = scroll {
+            history = history.scroll(scroll);
+        }
+
+        let token = self.token.as_ref() as &dyn TokenBehavior;
+        user::create_or_update_history(&self.base_url, token, history).await?;
+
+        self.pending = None;
+
+        Ok(())
+    }
+}
+
+/// `retries`번째 재시도까지 기다릴 시간, `MAX_RETRY_INTERVAL`을 넘지 않음
+fn backoff(retry_interval: Duration, retries: usize) -> Duration {
+    u32::try_from(retries)
+        .ok()
+        .and_then(|x| 2_u32.checked_pow(x))
+        .and_then(|x| retry_interval.checked_mul(x))
+        .map_or(MAX_RETRY_INTERVAL, |x| x.min(MAX_RETRY_INTERVAL))
+}
+
+/// 네트워크 에러나 서버 에러만 다시 보냄
+fn is_retriable(err: &user::Error) -> bool {
+    let user::Error::Base(err) = err else {
+        return false;
+    };
+
+    match err {
+        BaseError::Reqwest(_) | BaseError::BodyRead(_) | BaseError::Transport(_) => true,
+        BaseError::Undefined(status, _) => status.is_server_error(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let interval = Duration::from_secs(1);
+
+        assert_eq!(backoff(interval, 0), interval);
+        assert_eq!(backoff(interval, 3), Duration::from_secs(8));
+        assert_eq!(backoff(interval, 31), MAX_RETRY_INTERVAL);
+        assert_eq!(backoff(interval, 32), MAX_RETRY_INTERVAL);
+        assert_eq!(backoff(interval, usize::MAX), MAX_RETRY_INTERVAL);
+
+        let mut worker = Worker {
+            base_url: String::new(),
+            token: Default::default(),
+            book_id: 1,
+            options: Options {
+                debounce: DEFAULT_DEBOUNCE,
+                retry_interval: interval,
+                max_retries: usize::MAX,
+            },
+            pending: Some((1, None)),
+            last: None,
+            completed: false,
+            retries: 0,
+        };
+
+        let err = Err(user::Error::Base(BaseError::Undefined(
+            StatusCode::SERVICE_UNAVAILABLE,
+            String::new(),
+        )));
+
+        for _ in 0..100 {
+            let at = worker.next_retry(&err).unwrap();
+            assert!(at <= Instant::now() + MAX_RETRY_INTERVAL);
+        }
+        assert_eq!(worker.retries, 100);
+    }
+
+    #[cfg(feature = "mock")]
+    #[tokio::test(start_paused = true)]
+    async fn test_reading_session() {
+        use crate::mock::{
+            fixtures::{book, login},
+            Failure, MockServer,
+        };
+
+        const NAME: &str = "create_or_update_history";
+
+        let server = MockServer::start().await.unwrap();
+        let client = login(&server).await;
+
+        server.seed_book(book(1, 10));
+
+        let histories = || async { client.user().get_my_histories(25, 1).await.unwrap() };
+
+        let debounce = Duration::from_millis(100);
+        let mut session = client
+            .reading_session(1)
+            .debounce(debounce)
+            .retry_interval(Duration::from_millis(50));
+
+        // 멈춰있는 시계는 background 요청만 남았을 때 다음 타이머까지 넘어감
+        let started = Instant::now();
+        session.page(1);
+        session.page(2);
+        session.page(3);
+
+        server.wait_responses("user", NAME, 1).await;
+        assert!(started.elapsed() >= debounce);
+        assert_eq!(server.calls("user", NAME), 1);
+        assert_eq!(histories().await[0].page(), Some(3));
+
+        // 실패하면 background에서 다시 보냄
+        server.fail(
+            "user",
+            NAME,
+            Failure::status(StatusCode::SERVICE_UNAVAILABLE).times(1),
+        );
+        session.page(4);
+        assert!(session.pause().await.is_err());
+
+        server.wait_responses("user", NAME, 3).await;
+        assert_eq!(histories().await[0].page(), Some(4));
+
+        session.close(true).await.unwrap();
+        let history = &histories().await[0];
+        assert_eq!(history.page(), Some(4));
+        assert!(history.is_completed());
+
+        // 페이지 없이 완독으로 끝낼 수 없음
+        let session = client.reading_session(1);
+        assert!(matches!(
+            session.close(true).await,
+            Err(SessionError::NoPage)
+        ));
+        assert!(client.reading_session(1).close(false).await.is_ok());
+        assert_eq!(server.calls("user", NAME), 4);
+    }
+}
@@ -1,6 +1,7 @@
 use http::HeaderMap;
 use parking_lot::RwLock;
 use util::http::{Cookie, SetCookie};
+use uuid::Uuid;
 
 use crate::api::{
     cookie::{MADOME_ACCESS_TOKEN, MADOME_REFRESH_TOKEN},
@@ -13,6 +14,7 @@ impl From<String> for AuthStore {
     fn from(access_token: String) -> Self {
         Self {
             token: RwLock::new(Some((access_token, String::new()))),
+            udid: Default::default(),
         }
     }
 }
@@ -27,6 +29,7 @@ impl From<TokenPair> for AuthStore {
     fn from(token: TokenPair) -> Self {
         Self {
             token: RwLock::new(Some(token)),
+            udid: Default::default(),
         }
     }
 }
@@ -40,14 +43,26 @@ impl From<(&str, &str)> for AuthStore {
 #[derive(Default)]
 pub struct AuthStore {
     token: RwLock<Option<TokenPair>>,
+    /// 이 설치본의 기기 id, FCM 토큰을 등록할 때 씀
+    udid: RwLock<Option<Uuid>>,
 }
 
 impl AuthStore {
     pub fn new(access_token: impl Into<String>, refresh_token: impl Into<String>) -> Self {
         Self {
             token: RwLock::new(Some((access_token.into(), refresh_token.into()))),
+            udid: Default::default(),
         }
     }
+
+    /// `set_udid`로 넣기 전에는 `None`
+    pub fn udid(&self) -> Option<Uuid> {
+        *self.udid.read()
+    }
+
+    pub fn set_udid(&self, udid: Uuid) {
+        self.udid.write().replace(udid);
+    }
 }
 
 impl From<&HeaderMap> for AuthStore {
@@ -3,7 +3,12 @@ pub mod api;
 #[cfg(feature = "client")]
 mod client;
 #[cfg(feature = "client")]
-pub use client::MadomeClient;
+pub use client::{device, join, notification, session, MadomeClient};
+
+#[cfg(feature = "cassette")]
+pub mod cassette;
+#[cfg(feature = "mock")]
+pub mod mock;
 
 /* use madome_sdk_macros::impl_into_args;
 
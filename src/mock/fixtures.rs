@@ -0,0 +1,48 @@
+//! mock 서버를 쓰는 테스트에서 같이 쓰는 데이터
+
+use uuid::Uuid;
+
+use crate::{
+    api::{library, user},
+    MadomeClient,
+};
+
+use super::MockServer;
+
+pub(crate) const EMAIL: &str = "madome@madome.app";
+
+/// 유저를 넣고 그 유저로 로그인한 client, 유저의 id는 `Uuid::from_u128(1)`
+pub(crate) async fn login(server: &MockServer) -> MadomeClient {
+    let now = chrono::Utc::now();
+
+    server.seed_user(user::model::User {
+        id: Uuid::from_u128(1),
+        name: "madome".to_string(),
+        email: EMAIL.to_string(),
+        role: 0,
+        created_at: now,
+        updated_at: now,
+    });
+
+    let client = server.client();
+    client.auth().create_authcode(EMAIL).await.unwrap();
+    let code = server.authcode(EMAIL).unwrap();
+    client.auth().create_token_pair(EMAIL, code).await.unwrap();
+
+    client
+}
+
+pub(crate) fn book(id: u32, page: usize) -> library::model::Book {
+    let now = chrono::Utc::now();
+
+    library::model::Book {
+        id,
+        title: format!("title {id}"),
+        tags: vec![],
+        kind: library::model::BookKind::Doujinshi,
+        language: library::model::Language::Korean,
+        page,
+        created_at: now,
+        updated_at: now,
+    }
+}
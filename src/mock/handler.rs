@@ -0,0 +1,1029 @@
+#![allow(clippy::result_large_err)]
+
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use chrono::Utc;
+use http::{header, HeaderMap, HeaderValue, Response, StatusCode};
+use hyper::Body;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::{
+    auth,
+    cookie::{MADOME_ACCESS_TOKEN, MADOME_REFRESH_TOKEN},
+    library, user,
+};
+
+use super::{mail::Mail, state::State};
+
+/// 한 email로 동시에 발급할 수 있는 인증코드 수
+const MAX_AUTHCODES: usize = 5;
+
+pub(crate) struct MockRequest {
+    pub params: HashMap<String, String>,
+    pub query: String,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl MockRequest {
+    fn param(&self, name: &str) -> &str {
+        self.params
+            .get(name)
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    fn query<T: DeserializeOwned>(&self) -> Result<T, Response<Body>> {
+        serde_qs::from_str(&self.query).map_err(|err| text(StatusCode::BAD_REQUEST, err))
+    }
+
+    fn json<T: DeserializeOwned>(&self) -> Result<T, Response<Body>> {
+        serde_json::from_slice(&self.body).map_err(|err| text(StatusCode::BAD_REQUEST, err))
+    }
+
+    fn cookie(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|x| x.to_str().ok())
+            .flat_map(|x| x.split(';'))
+            .filter_map(|x| x.trim().split_once('='))
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v)
+    }
+
+    /// 요청한 유저의 id
+    fn authenticate(&self, state: &State) -> Result<Uuid, Response<Body>> {
+        self.cookie(MADOME_ACCESS_TOKEN)
+            .and_then(|x| state.access_tokens.get(x))
+            .copied()
+            .ok_or_else(|| empty(StatusCode::UNAUTHORIZED))
+    }
+}
+
+pub(crate) fn empty(status: StatusCode) -> Response<Body> {
+    let mut resp = Response::new(Body::empty());
+    *resp.status_mut() = status;
+    resp
+}
+
+pub(crate) fn text(status: StatusCode, msg: impl ToString) -> Response<Body> {
+    let mut resp = Response::new(Body::from(msg.to_string()));
+    *resp.status_mut() = status;
+    resp
+}
+
+fn json<T: Serialize>(status: StatusCode, x: &T) -> Response<Body> {
+    match serde_json::to_vec(x) {
+        Ok(buf) => {
+            let mut resp = Response::new(Body::from(buf));
+            *resp.status_mut() = status;
+            resp.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+            resp
+        }
+        Err(err) => text(StatusCode::INTERNAL_SERVER_ERROR, err),
+    }
+}
+
+/// `(namespace, endpoint name)`에 맞는 handler를 실행함
+pub(crate) fn handle(
+    state: &mut State,
+    namespace: &str,
+    name: &str,
+    req: &MockRequest,
+) -> Response<Body> {
+    let r = match (namespace, name) {
+        ("auth", "create_authcode") => create_authcode(state, req),
+        ("auth", "create_token_pair") => create_token_pair(state, req),
+        ("auth", "check_access_token") => check_access_token(state, req),
+        ("auth", "refresh_token_pair") => refresh_token_pair(state, req),
+
+        ("user", "create_user") => create_user(state, req),
+        ("user", "get_user") => get_user(state, req),
+        ("user", "get_likes") => get_likes(state, req),
+        ("user", "get_histories") => get_histories(state, req),
+        ("user", "update_user") => req
+            .authenticate(state)
+            .and_then(|user_id| update_user(state, user_id, req)),
+        ("user", "delete_user") => req
+            .authenticate(state)
+            .and_then(|user_id| delete_user(state, user_id, req)),
+
+        ("user", _) => req
+            .authenticate(state)
+            .and_then(|user_id| handle_me(state, user_id, name, req)),
+
+        ("library", _) => req
+            .authenticate(state)
+            .and_then(|_| handle_library(state, name, req)),
+
+        _ => Ok(text(
+            StatusCode::NOT_IMPLEMENTED,
+            format!("{namespace}::{name} is not implemented in mock"),
+        )),
+    };
+
+    r.unwrap_or_else(|resp| resp)
+}
+
+fn handle_library(
+    state: &mut State,
+    name: &str,
+    req: &MockRequest,
+) -> Result<Response<Body>, Response<Body>> {
+    match name {
+        "get_book_by_id" => get_book_by_id(state, req),
+        "get_books" => get_books(state, req),
+        "get_books_by_ids" => get_books_by_ids(state, req),
+        "get_books_by_tags" => get_books_by_tags(state, req),
+        "get_book_image_list" => get_book_image_list(state, req),
+        "get_book_image" => get_book_image(state, req),
+        _ => Ok(text(
+            StatusCode::NOT_IMPLEMENTED,
+            format!("library::{name} is not implemented in mock"),
+        )),
+    }
+}
+
+fn handle_me(
+    state: &mut State,
+    user_id: Uuid,
+    name: &str,
+    req: &MockRequest,
+) -> Result<Response<Body>, Response<Body>> {
+    match name {
+        "get_me" => get_me(state, user_id),
+        "update_me" => update_me(state, user_id, req),
+        "delete_me" => delete_me(state, user_id),
+        "create_or_update_fcm_token" => create_or_update_fcm_token(state, user_id, req),
+        "get_devices" => get_devices(state, user_id),
+        "delete_device" => delete_device(state, user_id, req),
+        "get_my_likes" => get_my_likes(state, user_id, req),
+        "create_like" => create_like(state, user_id, req),
+        "delete_like" => delete_like(state, user_id, req),
+        "create_likes" => create_likes(state, user_id, req),
+        "delete_likes" => delete_likes(state, user_id, req),
+        "is_liked" => is_liked(state, user_id, req),
+        "get_notifications" => get_notifications(state, user_id, req),
+        "get_unread_notification_count" => get_unread_notification_count(state, user_id),
+        "read_notification" => read_notification(state, user_id, req),
+        "read_all_notifications" => read_all_notifications(state, user_id),
+        "get_my_histories" => get_my_histories(state, user_id, req),
+        "create_or_update_history" => create_or_update_history(state, user_id, req),
+        _ => Ok(text(
+            StatusCode::NOT_IMPLEMENTED,
+            format!("user::{name} is not implemented in mock"),
+        )),
+    }
+}
+
+type Reply = Result<Response<Body>, Response<Body>>;
+
+/*
+    auth
+*/
+
+fn token_pair(state: &mut State, user_id: Uuid) -> Response<Body> {
+    let n = state.next();
+    let access = format!("access.{user_id}.{n}");
+    let refresh = format!("refresh.{user_id}.{n}");
+
+    state.access_tokens.insert(access.clone(), user_id);
+    state.refresh_tokens.insert(refresh.clone(), user_id);
+
+    let mut resp = empty(StatusCode::OK);
+
+    for (name, value) in [
+        (MADOME_ACCESS_TOKEN, access),
+        (MADOME_REFRESH_TOKEN, refresh),
+    ] {
+        let set_cookie = format!("{name}={value}; Path=/; HttpOnly; SameSite=Lax");
+
+        if let Ok(x) = HeaderValue::from_str(&set_cookie) {
+            resp.headers_mut().append(header::SET_COOKIE, x);
+        }
+    }
+
+    resp
+}
+
+fn create_authcode(state: &mut State, req: &MockRequest) -> Reply {
+    #[derive(Deserialize)]
+    struct Payload {
+        email: String,
+    }
+
+    let Payload { email } = req.json()?;
+
+    if state.find_user(&email).is_none() {
+        return Err(empty(StatusCode::NOT_FOUND));
+    }
+
+    let created = state.authcodes.iter().filter(|(x, _)| *x == email).count();
+
+    if created >= MAX_AUTHCODES {
+        return Err(empty(StatusCode::TOO_MANY_REQUESTS));
+    }
+
+    let code = format!("{:06}", state.next() * 7919 % 1_000_000);
+
+    if state.smtp.is_some() {
+        state.outbox.push(Mail {
+            from: "noreply@madome.app".to_string(),
+            to: vec![email.clone()],
+            subject: "Madome 인증 코드".to_string(),
+            body: format!("인증 코드: {code}"),
+        });
+    }
+
+    state.authcodes.push((email, code));
+
+    Ok(empty(StatusCode::CREATED))
+}
+
+fn create_token_pair(state: &mut State, req: &MockRequest) -> Reply {
+    #[derive(Deserialize)]
+    struct Payload {
+        email: String,
+        code: String,
+    }
+
+    let Payload { email, code } = req.json()?;
+
+    let position = state
+        .authcodes
+        .iter()
+        .position(|x| x.0 == email && x.1 == code)
+        .ok_or_else(|| empty(StatusCode::NOT_FOUND))?;
+
+    let user_id = state
+        .find_user(&email)
+        .map(|x| x.id)
+        .ok_or_else(|| empty(StatusCode::NOT_FOUND))?;
+
+    state.authcodes.remove(position);
+
+    let mut resp = token_pair(state, user_id);
+    *resp.status_mut() = StatusCode::CREATED;
+
+    Ok(resp)
+}
+
+fn check_access_token(state: &mut State, req: &MockRequest) -> Reply {
+    #[derive(Deserialize)]
+    struct Query {
+        role: Option<u8>,
+    }
+
+    let Query { role } = req.query()?;
+    let user_id = req.authenticate(state)?;
+
+    let user = state
+        .find_user(&user_id.to_string())
+        .ok_or_else(|| empty(StatusCode::UNAUTHORIZED))?;
+
+    if user.role < role.unwrap_or_default() {
+        return Err(empty(StatusCode::FORBIDDEN));
+    }
+
+    Ok(json(StatusCode::OK, &auth::model::UserId { user_id }))
+}
+
+fn refresh_token_pair(state: &mut State, req: &MockRequest) -> Reply {
+    let user_id = req
+        .cookie(MADOME_REFRESH_TOKEN)
+        .and_then(|x| state.refresh_tokens.remove(x))
+        .ok_or_else(|| empty(StatusCode::UNAUTHORIZED))?;
+
+    if let Some(access) = req.cookie(MADOME_ACCESS_TOKEN) {
+        state.access_tokens.remove(access);
+    }
+
+    Ok(token_pair(state, user_id))
+}
+
+/*
+    user
+*/
+
+fn create_user(state: &mut State, req: &MockRequest) -> Reply {
+    #[derive(Deserialize)]
+    struct Payload {
+        name: String,
+        email: String,
+        role: Option<u8>,
+    }
+
+    let Payload { name, email, role } = req.json()?;
+
+    if state.find_user(&email).is_some() {
+        return Err(empty(StatusCode::CONFLICT));
+    }
+
+    let now = Utc::now();
+    let id = Uuid::from_u64_pair(u64::MAX, state.next());
+
+    state.users.push(user::model::User {
+        id,
+        name,
+        email,
+        role: role.unwrap_or_default(),
+        created_at: now,
+        updated_at: now,
+    });
+
+    Ok(empty(StatusCode::OK))
+}
+
+#[derive(Deserialize)]
+struct UpdateUserPayload {
+    name: Option<String>,
+    role: Option<u8>,
+}
+
+fn update_user_by_id(
+    state: &mut State,
+    user_id: Uuid,
+    UpdateUserPayload { name, role }: UpdateUserPayload,
+) -> Reply {
+    let user = state
+        .users
+        .iter_mut()
+        .find(|x| x.id == user_id)
+        .ok_or_else(|| empty(StatusCode::NOT_FOUND))?;
+
+    if let Some(name) = name {
+        user.name = name;
+    }
+    if let Some(role) = role {
+        user.role = role;
+    }
+    user.updated_at = Utc::now();
+
+    Ok(json(StatusCode::OK, user))
+}
+
+fn delete_user_by_id(state: &mut State, user_id: Uuid) -> Reply {
+    if !state.users.iter().any(|x| x.id == user_id) {
+        return Err(empty(StatusCode::NOT_FOUND));
+    }
+
+    state.users.retain(|x| x.id != user_id);
+    state.likes.remove(&user_id);
+    state.histories.remove(&user_id);
+    state.notifications.remove(&user_id);
+    state.devices.remove(&user_id);
+    state.access_tokens.retain(|_, x| *x != user_id);
+    state.refresh_tokens.retain(|_, x| *x != user_id);
+
+    Ok(empty(StatusCode::NO_CONTENT))
+}
+
+/// 요청한 유저의 role이 대상 유저, 바꾸려는 role보다 높아야 함
+fn check_role(
+    state: &State,
+    requester_id: Uuid,
+    req: &MockRequest,
+    role: Option<u8>,
+) -> Result<Uuid, Response<Body>> {
+    let requester = state
+        .find_user(&requester_id.to_string())
+        .map(|x| x.role)
+        .ok_or_else(|| empty(StatusCode::UNAUTHORIZED))?;
+
+    let user = state
+        .find_user(req.param("user_id_or_email"))
+        .ok_or_else(|| empty(StatusCode::NOT_FOUND))?;
+
+    if requester <= user.role || role.is_some_and(|x| requester <= x) {
+        return Err(empty(StatusCode::FORBIDDEN));
+    }
+
+    Ok(user.id)
+}
+
+fn update_user(state: &mut State, requester_id: Uuid, req: &MockRequest) -> Reply {
+    let payload: UpdateUserPayload = req.json()?;
+    let user_id = check_role(state, requester_id, req, payload.role)?;
+
+    update_user_by_id(state, user_id, payload)
+}
+
+fn delete_user(state: &mut State, requester_id: Uuid, req: &MockRequest) -> Reply {
+    let user_id = check_role(state, requester_id, req, None)?;
+
+    delete_user_by_id(state, user_id)
+}
+
+fn get_user(state: &mut State, req: &MockRequest) -> Reply {
+    let user = state
+        .find_user(req.param("user_id_or_email"))
+        .ok_or_else(|| empty(StatusCode::NOT_FOUND))?;
+
+    Ok(json(StatusCode::OK, user))
+}
+
+fn get_likes(state: &mut State, req: &MockRequest) -> Reply {
+    let user_id = state
+        .find_user(req.param("user_id"))
+        .map(|x| x.id)
+        .ok_or_else(|| empty(StatusCode::NOT_FOUND))?;
+
+    let likes = state.likes.get(&user_id).cloned().unwrap_or_default();
+
+    Ok(json(StatusCode::OK, &likes))
+}
+
+fn get_histories(state: &mut State, req: &MockRequest) -> Reply {
+    let user_id = state
+        .find_user(req.param("user_id"))
+        .map(|x| x.id)
+        .ok_or_else(|| empty(StatusCode::NOT_FOUND))?;
+
+    let histories = state.histories.get(&user_id).cloned().unwrap_or_default();
+
+    Ok(json(StatusCode::OK, &histories))
+}
+
+/*
+    user @me
+*/
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct PageQuery {
+    kind: Option<String>,
+    per_page: usize,
+    page: usize,
+    sort_by: Option<String>,
+}
+
+fn paginate<T>(xs: Vec<T>, per_page: usize, page: usize) -> Vec<T> {
+    xs.into_iter()
+        .skip(per_page * page.saturating_sub(1))
+        .take(per_page)
+        .collect()
+}
+
+/// `{ "kind": "book", ... }`의 kind
+fn kind_of(x: &impl Serialize) -> String {
+    serde_json::to_value(x)
+        .ok()
+        .and_then(|x| x["kind"].as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum LikePayload {
+    Book {
+        book_id: u32,
+    },
+    BookTag {
+        #[serde(flatten, with = "user::model::flat_tag")]
+        tag: library::model::Tag,
+    },
+}
+
+impl LikePayload {
+    fn is(&self, like: &user::model::Like) -> bool {
+        use user::model::Like;
+
+        match (self, like) {
+            (Self::Book { book_id }, Like::Book { book_id: x, .. }) => book_id == x,
+            (Self::BookTag { tag }, Like::BookTag { tag: x, .. }) => tag == x,
+            _ => false,
+        }
+    }
+}
+
+fn get_me(state: &mut State, user_id: Uuid) -> Reply {
+    let user = state
+        .find_user(&user_id.to_string())
+        .ok_or_else(|| empty(StatusCode::NOT_FOUND))?;
+
+    Ok(json(StatusCode::OK, user))
+}
+
+fn update_me(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    #[derive(Deserialize)]
+    struct Payload {
+        name: Option<String>,
+    }
+
+    let Payload { name } = req.json()?;
+
+    update_user_by_id(state, user_id, UpdateUserPayload { name, role: None })
+}
+
+fn delete_me(state: &mut State, user_id: Uuid) -> Reply {
+    delete_user_by_id(state, user_id)
+}
+
+fn create_or_update_fcm_token(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    #[derive(Deserialize)]
+    struct Payload {
+        udid: Uuid,
+        fcm_token: String,
+    }
+
+    let Payload { udid, fcm_token } = req.json()?;
+
+    let now = Utc::now();
+    let devices = state.devices.entry(user_id).or_default();
+
+    match devices.iter_mut().find(|x| x.udid == udid) {
+        Some(device) => {
+            device.fcm_token = fcm_token;
+            device.updated_at = now;
+        }
+        None => devices.push(user::model::Device {
+            udid,
+            fcm_token,
+            created_at: now,
+            updated_at: now,
+        }),
+    }
+
+    Ok(empty(StatusCode::CREATED))
+}
+
+fn get_devices(state: &mut State, user_id: Uuid) -> Reply {
+    let devices = state.devices.get(&user_id).cloned().unwrap_or_default();
+
+    Ok(json(StatusCode::OK, &devices))
+}
+
+fn delete_device(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    let udid = req.param("udid");
+    let devices = state.devices.entry(user_id).or_default();
+
+    let position = devices
+        .iter()
+        .position(|x| x.udid.to_string() == udid)
+        .ok_or_else(|| empty(StatusCode::NOT_FOUND))?;
+
+    devices.remove(position);
+
+    Ok(empty(StatusCode::NO_CONTENT))
+}
+
+fn get_my_likes(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    let query: PageQuery = req.query()?;
+
+    let mut likes = state
+        .likes
+        .get(&user_id)
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|x| query.kind.as_ref().is_none_or(|kind| kind_of(x) == *kind))
+        .collect::<Vec<_>>();
+
+    let created_at = user::model::Like::created_at;
+
+    match query.sort_by.as_deref() {
+        Some("created-at-asc") => likes.sort_by_key(created_at),
+        _ => likes.sort_by_key(|x| std::cmp::Reverse(created_at(x))),
+    }
+
+    Ok(json(
+        StatusCode::OK,
+        &paginate(likes, query.per_page, query.page),
+    ))
+}
+
+/// 이미 있으면 `false`
+fn insert_like(likes: &mut Vec<user::model::Like>, payload: LikePayload) -> bool {
+    if likes.iter().any(|x| payload.is(x)) {
+        return false;
+    }
+
+    let created_at = Utc::now();
+
+    likes.push(match payload {
+        LikePayload::Book { book_id } => user::model::Like::Book {
+            book_id,
+            created_at,
+        },
+        LikePayload::BookTag { tag } => user::model::Like::BookTag { tag, created_at },
+    });
+
+    true
+}
+
+/// 없으면 `false`
+fn remove_like(likes: &mut Vec<user::model::Like>, payload: &LikePayload) -> bool {
+    let len = likes.len();
+    likes.retain(|x| !payload.is(x));
+
+    likes.len() != len
+}
+
+fn create_like(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    let payload: LikePayload = req.json()?;
+
+    if !insert_like(state.likes.entry(user_id).or_default(), payload) {
+        return Err(empty(StatusCode::CONFLICT));
+    }
+
+    Ok(empty(StatusCode::CREATED))
+}
+
+fn delete_like(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    let payload: LikePayload = req.json()?;
+
+    if !remove_like(state.likes.entry(user_id).or_default(), &payload) {
+        return Err(empty(StatusCode::NOT_FOUND));
+    }
+
+    Ok(empty(StatusCode::NO_CONTENT))
+}
+
+#[derive(Deserialize)]
+struct LikesPayload {
+    likes: Vec<LikePayload>,
+}
+
+fn create_likes(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    use user::model::LikeResult;
+
+    let LikesPayload { likes: payloads } = req.json()?;
+    let likes = state.likes.entry(user_id).or_default();
+
+    let r = payloads
+        .into_iter()
+        .map(|payload| match insert_like(likes, payload) {
+            true => LikeResult::Created,
+            false => LikeResult::AlreadyExists,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(json(StatusCode::OK, &r))
+}
+
+fn delete_likes(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    use user::model::LikeResult;
+
+    let LikesPayload { likes: payloads } = req.json()?;
+    let likes = state.likes.entry(user_id).or_default();
+
+    let r = payloads
+        .iter()
+        .map(|payload| match remove_like(likes, payload) {
+            true => LikeResult::Deleted,
+            false => LikeResult::NotFound,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(json(StatusCode::OK, &r))
+}
+
+fn is_liked(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    struct Query {
+        #[serde(default)]
+        book_ids: Vec<u32>,
+        #[serde(default)]
+        book_tags: Vec<library::model::Tag>,
+    }
+
+    let query: Query = req.query()?;
+    let likes = state.likes.get(&user_id).cloned().unwrap_or_default();
+
+    let book_ids = query
+        .book_ids
+        .into_iter()
+        .filter(|&book_id| likes.iter().any(|x| LikePayload::Book { book_id }.is(x)))
+        .collect();
+
+    let book_tags = query
+        .book_tags
+        .into_iter()
+        .filter(|tag| likes.iter().any(|x| x.tag() == Some(tag)))
+        .collect();
+
+    Ok(json(
+        StatusCode::OK,
+        &user::model::LikeState {
+            book_ids,
+            book_tags,
+        },
+    ))
+}
+
+fn get_notifications(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    #[derive(Deserialize)]
+    struct Query {
+        unread: Option<bool>,
+    }
+
+    let query: PageQuery = req.query()?;
+    let Query { unread } = req.query()?;
+
+    let mut notifications = state
+        .notifications
+        .get(&user_id)
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|x| query.kind.as_ref().is_none_or(|kind| kind_of(x) == *kind))
+        .filter(|x| !(unread == Some(true) && x.is_read()))
+        .collect::<Vec<_>>();
+
+    match query.sort_by.as_deref() {
+        Some("created-at-asc") => notifications.sort_by_key(|x| x.created_at()),
+        _ => notifications.sort_by_key(|x| std::cmp::Reverse(x.created_at())),
+    }
+
+    Ok(json(
+        StatusCode::OK,
+        &paginate(notifications, query.per_page, query.page),
+    ))
+}
+
+fn get_unread_notification_count(state: &mut State, user_id: Uuid) -> Reply {
+    let count = state
+        .notifications
+        .get(&user_id)
+        .map(|xs| xs.iter().filter(|x| !x.is_read()).count())
+        .unwrap_or_default();
+
+    Ok(json(StatusCode::OK, &count))
+}
+
+fn read_notification(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    use user::model::Notification;
+
+    let notification_id = req.param("notification_id");
+
+    let notification = state
+        .notifications
+        .entry(user_id)
+        .or_default()
+        .iter_mut()
+        .find(|x| x.id().is_some_and(|id| id.to_string() == notification_id))
+        .ok_or_else(|| empty(StatusCode::NOT_FOUND))?;
+
+    if let Notification::Book { read_at, .. } = notification {
+        read_at.get_or_insert_with(Utc::now);
+    }
+
+    Ok(empty(StatusCode::NO_CONTENT))
+}
+
+fn read_all_notifications(state: &mut State, user_id: Uuid) -> Reply {
+    use user::model::Notification;
+
+    let now = Utc::now();
+
+    for notification in state.notifications.entry(user_id).or_default() {
+        if let Notification::Book { read_at, .. } = notification {
+            read_at.get_or_insert(now);
+        }
+    }
+
+    Ok(empty(StatusCode::NO_CONTENT))
+}
+
+fn get_my_histories(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    use user::model::History;
+
+    let query: PageQuery = req.query()?;
+
+    let mut histories = state
+        .histories
+        .get(&user_id)
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|x| query.kind.as_ref().is_none_or(|kind| kind_of(x) == *kind))
+        .collect::<Vec<_>>();
+
+    let created_at = History::created_at;
+    let updated_at = History::updated_at;
+
+    match query.sort_by.as_deref() {
+        Some("created-at-asc") => histories.sort_by_key(created_at),
+        Some("updated-at-asc") => histories.sort_by_key(updated_at),
+        Some("updated-at-desc") => histories.sort_by_key(|x| std::cmp::Reverse(updated_at(x))),
+        _ => histories.sort_by_key(|x| std::cmp::Reverse(created_at(x))),
+    }
+
+    Ok(json(
+        StatusCode::OK,
+        &paginate(histories, query.per_page, query.page),
+    ))
+}
+
+fn create_or_update_history(state: &mut State, user_id: Uuid, req: &MockRequest) -> Reply {
+    use user::model::History;
+
+    #[derive(Deserialize)]
+    #[serde(tag = "kind", rename_all = "snake_case")]
+    enum Payload {
+        Book {
+            book_id: u32,
+            page: usize,
+            scroll: Option<f32>,
+            completed: bool,
+        },
+    }
+
+    let Payload::Book {
+        book_id,
+        page,
+        scroll,
+        completed,
+    } = req.json()?;
+
+    if !state.books.contains_key(&book_id) {
+        return Err(empty(StatusCode::NOT_FOUND));
+    }
+
+    let now = Utc::now();
+    let histories = state.histories.entry(user_id).or_default();
+
+    let created_at = histories
+        .iter()
+        .position(|x| x.book_id() == Some(book_id))
+        .and_then(|i| histories.remove(i).created_at())
+        .unwrap_or(now);
+
+    histories.push(History::Book {
+        book_id,
+        page,
+        scroll,
+        completed,
+        created_at,
+        updated_at: now,
+    });
+
+    Ok(empty(StatusCode::CREATED))
+}
+
+/*
+    library
+*/
+
+fn book_id(req: &MockRequest) -> Result<u32, Response<Body>> {
+    req.param("book_id")
+        .parse()
+        .map_err(|err| text(StatusCode::BAD_REQUEST, err))
+}
+
+/// `payload::BookKind`(kebab-case)와 `model::BookKind`(snake_case)를 비교함
+fn is_kind(book: &library::model::Book, kind: &str) -> bool {
+    serde_json::to_value(book.kind)
+        .ok()
+        .and_then(|x| x.as_str().map(|x| x == kind.replace('-', "_")))
+        .unwrap_or(false)
+}
+
+/// `sort-by=id-asc`가 아니면 최신순, `random`도 테스트를 위해 최신순으로 정렬함
+fn sort_and_paginate(
+    mut books: Vec<library::model::Book>,
+    sort_by: Option<&str>,
+    per_page: usize,
+    page: usize,
+) -> Vec<library::model::Book> {
+    match sort_by {
+        Some("id-asc") => books.sort_by_key(|x| x.id),
+        _ => books.sort_by_key(|x| std::cmp::Reverse(x.id)),
+    }
+
+    books
+        .into_iter()
+        .skip(per_page * page.saturating_sub(1))
+        .take(per_page)
+        .collect()
+}
+
+fn get_book_by_id(state: &mut State, req: &MockRequest) -> Reply {
+    let book = state
+        .books
+        .get(&book_id(req)?)
+        .ok_or_else(|| empty(StatusCode::NOT_FOUND))?;
+
+    Ok(json(StatusCode::OK, book))
+}
+
+fn get_books(state: &mut State, req: &MockRequest) -> Reply {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    struct Query {
+        kind: Option<String>,
+        language: Option<library::model::Language>,
+        per_page: Option<usize>,
+        page: Option<usize>,
+        sort_by: Option<String>,
+    }
+
+    let query: Query = req.query()?;
+
+    let books = state
+        .books
+        .values()
+        .filter(|x| query.kind.as_ref().is_none_or(|kind| is_kind(x, kind)))
+        .filter(|x| query.language.as_ref().is_none_or(|x2| x.language == *x2))
+        .cloned()
+        .collect();
+
+    let books = sort_and_paginate(
+        books,
+        query.sort_by.as_deref(),
+        query.per_page.unwrap_or(25),
+        query.page.unwrap_or(1),
+    );
+
+    Ok(json(StatusCode::OK, &books))
+}
+
+fn get_books_by_ids(state: &mut State, req: &MockRequest) -> Reply {
+    #[derive(Deserialize)]
+    struct Query {
+        ids: Vec<u32>,
+    }
+
+    let Query { ids } = req.query()?;
+
+    let books = ids
+        .iter()
+        .filter_map(|id| state.books.get(id))
+        .collect::<Vec<_>>();
+
+    Ok(json(StatusCode::OK, &books))
+}
+
+fn get_books_by_tags(state: &mut State, req: &MockRequest) -> Reply {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    struct Query {
+        tags: Vec<library::model::Tag>,
+        per_page: usize,
+        page: usize,
+        sort_by: Option<String>,
+    }
+
+    let query: Query = req.query()?;
+
+    let r = query
+        .tags
+        .into_iter()
+        .map(|tag| {
+            let books = state
+                .books
+                .values()
+                .filter(|x| x.tags.contains(&tag))
+                .cloned()
+                .collect();
+
+            let books =
+                sort_and_paginate(books, query.sort_by.as_deref(), query.per_page, query.page);
+
+            (tag, books)
+        })
+        .collect::<Vec<_>>();
+
+    Ok(json(StatusCode::OK, &r))
+}
+
+fn get_book_image_list(state: &mut State, req: &MockRequest) -> Reply {
+    let book_id = book_id(req)?;
+
+    if !state.books.contains_key(&book_id) {
+        return Err(empty(StatusCode::NOT_FOUND));
+    }
+
+    let images = state
+        .images
+        .get(&book_id)
+        .map(|xs| xs.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    Ok(json(StatusCode::OK, &images))
+}
+
+fn get_book_image(state: &mut State, req: &MockRequest) -> Reply {
+    let file_name = req.param("file_name");
+
+    let (_, image) = state
+        .images
+        .get(&book_id(req)?)
+        .and_then(|xs| xs.iter().find(|(name, _)| name == file_name))
+        .ok_or_else(|| empty(StatusCode::NOT_FOUND))?;
+
+    let mut resp = Response::new(Body::from(image.clone()));
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/octet-stream"),
+    );
+
+    Ok(resp)
+}
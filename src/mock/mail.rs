@@ -0,0 +1,380 @@
+//! 인증코드 메일을 받기 위한 로컬 SMTP 서버
+//!
+//! `MockServer::deliver_mail_to`로 연결하거나, 로컬에서 띄운 auth 서버의 SMTP 설정을 `MailSink::addr`로 바꿔서 씀
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use parking_lot::Mutex;
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader, Lines},
+    net::{TcpListener, TcpStream},
+    sync::{oneshot, watch},
+};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, thiserror::Error)]
+pub enum MailError {
+    #[error("Io: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Smtp: {0}")]
+    Smtp(String),
+
+    #[error("Timeout: no authcode for {0}")]
+    Timeout(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mail {
+    pub from: String,
+    pub to: Vec<String>,
+    pub subject: String,
+    pub body: String,
+}
+
+type AuthcodeParser = fn(&Mail) -> Option<String>;
+
+struct Inbox {
+    mails: Vec<Mail>,
+    /// `await_authcode`로 이미 꺼낸 메일
+    taken: Vec<bool>,
+}
+
+pub struct MailSink {
+    addr: SocketAddr,
+    inbox: Arc<Mutex<Inbox>>,
+    received: Arc<watch::Sender<usize>>,
+    parser: AuthcodeParser,
+    timeout: Duration,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MailSink {
+    /// `127.0.0.1`의 빈 port로 SMTP 서버를 띄움
+    pub async fn start() -> Result<Self, MailError> {
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+        let addr = listener.local_addr()?;
+
+        let inbox = Arc::new(Mutex::new(Inbox {
+            mails: Vec::new(),
+            taken: Vec::new(),
+        }));
+        let received = Arc::new(watch::channel(0).0);
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
+
+        let (task_inbox, task_received) = (inbox.clone(), received.clone());
+
+        tokio::spawn(async move {
+            loop {
+                let stream = tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => stream,
+                        Err(err) => {
+                            log::warn!("mail sink: {err}");
+                            continue;
+                        }
+                    },
+                };
+
+                let (inbox, received) = (task_inbox.clone(), task_received.clone());
+
+                tokio::spawn(async move {
+                    if let Err(err) = session(stream, inbox, received).await {
+                        log::warn!("mail sink: {err}");
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            addr,
+            inbox,
+            received,
+            parser: parse_authcode,
+            timeout: DEFAULT_TIMEOUT,
+            shutdown: Some(shutdown_tx),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 메일 본문에서 인증코드를 꺼내는 방법을 바꿈
+    pub fn authcode_parser(mut self, parser: AuthcodeParser) -> Self {
+        self.parser = parser;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn mails(&self) -> Vec<Mail> {
+        self.inbox.lock().mails.clone()
+    }
+
+    /// `email`로 온 메일 중 아직 꺼내지 않은 첫번째 메일의 인증코드
+    ///
+    /// 메일이 올 때까지 `timeout`만큼 기다림
+    pub async fn await_authcode(&self, email: &str) -> Result<String, MailError> {
+        let mut received = self.received.subscribe();
+
+        let wait = async {
+            loop {
+                if let Some(code) = self.take_authcode(email) {
+                    return code;
+                }
+
+                // sender는 self가 들고 있으므로 닫히지 않음
+                received.changed().await.ok();
+            }
+        };
+
+        tokio::time::timeout(self.timeout, wait)
+            .await
+            .map_err(|_| MailError::Timeout(email.to_string()))
+    }
+
+    fn take_authcode(&self, email: &str) -> Option<String> {
+        let mut inbox = self.inbox.lock();
+        let Inbox { mails, taken } = &mut *inbox;
+
+        mails
+            .iter()
+            .zip(taken.iter_mut())
+            .filter(|(mail, taken)| !**taken && mail.to.iter().any(|to| to == email))
+            .find_map(|(mail, taken)| {
+                let code = (self.parser)(mail)?;
+                *taken = true;
+                Some(code)
+            })
+    }
+}
+
+impl Drop for MailSink {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+/// 본문에서 숫자가 포함된 6~12자리 영숫자 단어를 인증코드로 봄
+fn parse_authcode(mail: &Mail) -> Option<String> {
+    mail.body
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .find(|x| (6..=12).contains(&x.len()) && x.chars().any(|c| c.is_ascii_digit()))
+        .map(str::to_string)
+}
+
+async fn session(
+    stream: TcpStream,
+    inbox: Arc<Mutex<Inbox>>,
+    received: Arc<watch::Sender<usize>>,
+) -> Result<(), MailError> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    reply(&mut writer, "220 madome-sdk mail sink").await?;
+
+    let mut from = String::new();
+    let mut to = Vec::new();
+
+    while let Some(line) = lines.next_line().await? {
+        let command = line.get(..4).unwrap_or(&line).to_ascii_uppercase();
+
+        match command.as_str() {
+            "HELO" | "EHLO" => reply(&mut writer, "250 OK").await?,
+            "MAIL" => {
+                from = address(&line);
+                to.clear();
+                reply(&mut writer, "250 OK").await?;
+            }
+            "RCPT" => {
+                to.push(address(&line));
+                reply(&mut writer, "250 OK").await?;
+            }
+            "DATA" => {
+                reply(&mut writer, "354 End data with <CR><LF>.<CR><LF>").await?;
+
+                let mut data = Vec::new();
+                while let Some(line) = lines.next_line().await? {
+                    if line == "." {
+                        break;
+                    }
+                    // dot-stuffing
+                    data.push(line.strip_prefix('.').map(str::to_string).unwrap_or(line));
+                }
+
+                let (subject, body) = parse_data(&data);
+
+                let mail = Mail {
+                    from: std::mem::take(&mut from),
+                    to: std::mem::take(&mut to),
+                    subject,
+                    body,
+                };
+
+                let count = {
+                    let mut inbox = inbox.lock();
+                    inbox.mails.push(mail);
+                    inbox.taken.push(false);
+                    inbox.mails.len()
+                };
+                received.send_replace(count);
+
+                reply(&mut writer, "250 OK").await?;
+            }
+            "RSET" => {
+                from.clear();
+                to.clear();
+                reply(&mut writer, "250 OK").await?;
+            }
+            "NOOP" => reply(&mut writer, "250 OK").await?,
+            "QUIT" => {
+                reply(&mut writer, "221 Bye").await?;
+                break;
+            }
+            _ => reply(&mut writer, "502 Command not implemented").await?,
+        }
+    }
+
+    Ok(())
+}
+
+async fn reply(writer: &mut (impl AsyncWrite + Unpin), line: &str) -> Result<(), MailError> {
+    writer.write_all(format!("{line}\r\n").as_bytes()).await?;
+    Ok(())
+}
+
+/// `MAIL FROM:<a@b.com>` -> `a@b.com`
+fn address(line: &str) -> String {
+    let x = line.split_once(':').map(|(_, x)| x).unwrap_or_default();
+
+    x.trim()
+        .trim_start_matches('<')
+        .split('>')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn parse_data(data: &[String]) -> (String, String) {
+    let split = data.iter().position(|x| x.is_empty()).unwrap_or(data.len());
+    let (headers, body) = data.split_at(split);
+
+    let subject = headers
+        .iter()
+        .find_map(|x| {
+            let (name, value) = x.split_once(':')?;
+            name.eq_ignore_ascii_case("subject")
+                .then(|| value.trim().to_string())
+        })
+        .unwrap_or_default();
+
+    let body = body.iter().skip(1).cloned().collect::<Vec<_>>().join("\n");
+
+    (subject, body)
+}
+
+/// 간단한 SMTP client, mock 서버가 인증코드 메일을 보낼 때 씀
+pub async fn send(addr: SocketAddr, mail: &Mail) -> Result<(), MailError> {
+    let stream = TcpStream::connect(addr).await?;
+    let (reader, mut writer) = stream.into_split();
+    let lines = &mut BufReader::new(reader).lines();
+
+    expect(lines, "220").await?;
+
+    reply(&mut writer, "EHLO localhost").await?;
+    expect(lines, "250").await?;
+
+    reply(&mut writer, &format!("MAIL FROM:<{}>", mail.from)).await?;
+    expect(lines, "250").await?;
+
+    for to in &mail.to {
+        reply(&mut writer, &format!("RCPT TO:<{to}>")).await?;
+        expect(lines, "250").await?;
+    }
+
+    reply(&mut writer, "DATA").await?;
+    expect(lines, "354").await?;
+
+    reply(&mut writer, &format!("From: <{}>", mail.from)).await?;
+    reply(&mut writer, &format!("To: <{}>", mail.to.join(">, <"))).await?;
+    reply(&mut writer, &format!("Subject: {}", mail.subject)).await?;
+    reply(&mut writer, "").await?;
+    for line in mail.body.lines() {
+        if line.starts_with('.') {
+            reply(&mut writer, &format!(".{line}")).await?;
+        } else {
+            reply(&mut writer, line).await?;
+        }
+    }
+    reply(&mut writer, ".").await?;
+    expect(lines, "250").await?;
+
+    reply(&mut writer, "QUIT").await?;
+    expect(lines, "221").await?;
+
+    Ok(())
+}
+
+async fn expect(lines: &mut Lines<impl AsyncBufRead + Unpin>, code: &str) -> Result<(), MailError> {
+    match lines.next_line().await? {
+        Some(x) if x.starts_with(code) => Ok(()),
+        x => Err(MailError::Smtp(x.unwrap_or_default())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{api::auth, api::user, mock::MockServer};
+
+    #[tokio::test]
+    async fn test_await_authcode() {
+        let sink = MailSink::start()
+            .await
+            .unwrap()
+            .timeout(Duration::from_millis(300));
+        let server = MockServer::start().await.unwrap();
+        server.deliver_mail_to(sink.addr());
+
+        let now = Utc::now();
+        let email = "madome@madome.app";
+
+        server.seed_user(user::model::User {
+            id: Uuid::from_u128(1),
+            name: "madome".to_string(),
+            email: email.to_string(),
+            role: 0,
+            created_at: now,
+            updated_at: now,
+        });
+
+        let base_url = server.base_url();
+
+        auth::create_authcode(&base_url, "", email).await.unwrap();
+
+        let code = sink.await_authcode(email).await.unwrap();
+        assert_eq!(Some(code.clone()), server.authcode(email));
+        assert_eq!(sink.mails()[0].subject, "Madome 인증 코드");
+
+        auth::create_token_pair(&base_url, "", email, code)
+            .await
+            .unwrap();
+
+        // 이미 꺼낸 메일은 다시 쓰지 않음
+        assert!(matches!(
+            sink.await_authcode(email).await,
+            Err(MailError::Timeout(_))
+        ));
+    }
+}
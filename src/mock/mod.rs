@@ -0,0 +1,877 @@
+//! 테스트를 위한 in-process Madome 서버
+//!
+//! auth, user, library endpoint를 메모리에 있는 데이터로 응답함
+
+#[cfg(all(test, feature = "client"))]
+pub(crate) mod fixtures;
+mod handler;
+mod mail;
+mod state;
+
+pub use mail::{send as send_mail, Mail, MailError, MailSink};
+pub use state::Failure;
+
+use std::{collections::HashMap, convert::Infallible, net::SocketAddr, sync::Arc};
+
+use bytes::Bytes;
+use http::{Request, Response, StatusCode};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Server,
+};
+use parking_lot::Mutex;
+use tokio::sync::{oneshot, Notify};
+use uuid::Uuid;
+
+use crate::api::{endpoints, library, user, Endpoint};
+
+use self::{
+    handler::{text, MockRequest},
+    state::State,
+};
+
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    /// 요청에 응답할 때마다 깨움
+    responded: Arc<Notify>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    /// `127.0.0.1`의 빈 port로 서버를 띄움
+    pub async fn start() -> Result<Self, hyper::Error> {
+        let state = Arc::new(Mutex::new(State::default()));
+        let responded = Arc::new(Notify::new());
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+        let svc_state = state.clone();
+        let svc_responded = responded.clone();
+        let make_svc = make_service_fn(move |_| {
+            let state = svc_state.clone();
+            let responded = svc_responded.clone();
+
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    handle_request(state.clone(), responded.clone(), request)
+                }))
+            }
+        });
+
+        let server = Server::try_bind(&SocketAddr::from(([127, 0, 0, 1], 0)))?.serve(make_svc);
+        let addr = server.local_addr();
+
+        tokio::spawn(server.with_graceful_shutdown(async {
+            shutdown_rx.await.ok();
+        }));
+
+        Ok(Self {
+            addr,
+            state,
+            responded,
+            shutdown: Some(shutdown_tx),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// auth, user, library 모두 같은 base url을 씀
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    #[cfg(feature = "client")]
+    pub fn client(&self) -> crate::MadomeClient {
+        crate::MadomeClient::custom(self.base_url())
+    }
+
+    pub fn seed_user(&self, user: user::model::User) {
+        let mut state = self.state.lock();
+
+        state.users.retain(|x| x.id != user.id);
+        state.users.push(user);
+    }
+
+    pub fn seed_book(&self, book: library::model::Book) {
+        self.state.lock().books.insert(book.id, book);
+    }
+
+    pub fn seed_image(&self, book_id: u32, file_name: impl Into<String>, image: impl Into<Bytes>) {
+        self.state
+            .lock()
+            .images
+            .entry(book_id)
+            .or_default()
+            .push((file_name.into(), image.into()));
+    }
+
+    pub fn seed_like(&self, user_id: Uuid, like: user::model::Like) {
+        self.state
+            .lock()
+            .likes
+            .entry(user_id)
+            .or_default()
+            .push(like);
+    }
+
+    pub fn seed_history(&self, user_id: Uuid, history: user::model::History) {
+        self.state
+            .lock()
+            .histories
+            .entry(user_id)
+            .or_default()
+            .push(history);
+    }
+
+    pub fn seed_notification(&self, user_id: Uuid, notification: user::model::Notification) {
+        self.state
+            .lock()
+            .notifications
+            .entry(user_id)
+            .or_default()
+            .push(notification);
+    }
+
+    /// 메일로 보내졌어야 할 가장 최근 인증코드
+    pub fn authcode(&self, email: &str) -> Option<String> {
+        self.state
+            .lock()
+            .authcodes
+            .iter()
+            .rev()
+            .find(|(x, _)| x == email)
+            .map(|(_, code)| code.clone())
+    }
+
+    /// 실제 auth 서버처럼 토큰을 발급함
+    pub fn issue_token_pair(&self, user_id: Uuid) -> (String, String) {
+        let mut state = self.state.lock();
+        let n = state.next();
+
+        let access = format!("access.{user_id}.{n}");
+        let refresh = format!("refresh.{user_id}.{n}");
+
+        state.access_tokens.insert(access.clone(), user_id);
+        state.refresh_tokens.insert(refresh.clone(), user_id);
+
+        (access, refresh)
+    }
+
+    /// `fail("library", "get_book_by_id", Failure::status(StatusCode::INTERNAL_SERVER_ERROR).times(1))`
+    pub fn fail(&self, namespace: &str, name: &str, failure: Failure) {
+        self.state
+            .lock()
+            .failures
+            .insert((namespace.to_string(), name.to_string()), failure);
+    }
+
+    pub fn clear_failures(&self) {
+        self.state.lock().failures.clear();
+    }
+
+    /// endpoint가 요청 받은 횟수, 주입한 실패로 응답한 요청도 셈
+    pub fn calls(&self, namespace: &str, name: &str) -> usize {
+        self.state
+            .lock()
+            .calls
+            .get(&(namespace.to_string(), name.to_string()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// endpoint가 `n`번 응답할 때까지 기다림, 응답하기 전에 데이터는 이미 바뀌어 있음
+    ///
+    /// background에서 보내는 요청을 시간에 기대지 않고 기다릴 때 씀
+    pub async fn wait_responses(&self, namespace: &str, name: &str, n: usize) {
+        let key = (namespace.to_string(), name.to_string());
+
+        loop {
+            let responded = self.responded.notified();
+
+            if self
+                .state
+                .lock()
+                .responses
+                .get(&key)
+                .copied()
+                .unwrap_or_default()
+                >= n
+            {
+                return;
+            }
+
+            responded.await;
+        }
+    }
+
+    /// `create_authcode`가 호출되면 인증코드 메일을 `smtp`로 보냄
+    ///
+    /// ```ignore
+    /// let sink = MailSink::start().await?;
+    /// server.deliver_mail_to(sink.addr());
+    ///
+    /// client.auth().create_authcode(email).await?;
+    /// let code = sink.await_authcode(email).await?;
+    /// ```
+    pub fn deliver_mail_to(&self, smtp: SocketAddr) {
+        self.state.lock().smtp = Some(smtp);
+    }
+
+    /// 모든 데이터와 주입한 실패를 지움
+    pub fn reset(&self) {
+        let mut state = self.state.lock();
+        let smtp = state.smtp;
+
+        *state = State::default();
+        state.smtp = smtp;
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+fn decode_path_segment(x: &str) -> String {
+    let bytes = x.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|x| std::str::from_utf8(x).ok())
+            .and_then(|x| u8::from_str_radix(x, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                decoded.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// `/books/:book_id`에 맞으면 path 인자를 반환함
+fn match_path(template: &str, path: &str) -> Option<HashMap<String, String>> {
+    let template = template.split('/').collect::<Vec<_>>();
+    let path = path.split('/').collect::<Vec<_>>();
+
+    if template.len() != path.len() {
+        return None;
+    }
+
+    let mut params = HashMap::new();
+
+    for (t, p) in template.into_iter().zip(path) {
+        match t.strip_prefix(':') {
+            Some(name) => {
+                params.insert(name.to_string(), decode_path_segment(p));
+            }
+            None if t == p => {}
+            None => return None,
+        }
+    }
+
+    Some(params)
+}
+
+/// querystring에 있는 최상위 key
+/// e.g. `ids[0]=1&per-page=25` -> `["ids", "per-page"]`
+fn query_keys(query: &str) -> Vec<String> {
+    query
+        .split('&')
+        .filter_map(|x| x.split(['=', '[']).next())
+        .filter(|x| !x.is_empty())
+        .map(|x| {
+            decode_path_segment(x)
+                .split('[')
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        })
+        .collect()
+}
+
+/// method와 path가 같은 endpoint가 여럿이면 querystring으로 구분함
+/// e.g. `get_books`, `get_books_by_ids`, `get_books_by_tags`
+fn route(
+    method: &str,
+    path: &str,
+    query: &str,
+) -> Option<(&'static Endpoint, HashMap<String, String>)> {
+    let keys = query_keys(query);
+
+    endpoints()
+        .filter(|x| x.method == method)
+        .filter_map(|x| match_path(x.path, path).map(|params| (x, params)))
+        .filter_map(|(x, params)| {
+            let required = x
+                .parameters_in(crate::api::endpoint::Placement::Query)
+                .filter(|p| !p.ty.starts_with("Option<"))
+                .map(|p| p.name.replace('_', "-"))
+                .collect::<Vec<_>>();
+
+            let matched = required.iter().all(|p| keys.contains(p));
+
+            matched.then_some((required.len(), x, params))
+        })
+        .max_by_key(|(score, ..)| *score)
+        .map(|(_, x, params)| (x, params))
+}
+
+async fn handle_request(
+    state: Arc<Mutex<State>>,
+    responded: Arc<Notify>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let (parts, body) = request.into_parts();
+
+    let body = match hyper::body::to_bytes(body).await {
+        Ok(x) => x,
+        Err(err) => return Ok(text(StatusCode::BAD_REQUEST, err)),
+    };
+
+    let path = parts.uri.path();
+    let query = parts.uri.query().unwrap_or_default();
+
+    let (endpoint, params) = match route(parts.method.as_str(), path, query) {
+        Some(x) => x,
+        None => return Ok(text(StatusCode::NOT_FOUND, "no route")),
+    };
+
+    log::debug!(
+        "mock: {} {path} -> {}::{}",
+        parts.method,
+        endpoint.namespace,
+        endpoint.name
+    );
+
+    let key = (endpoint.namespace.to_string(), endpoint.name.to_string());
+    *state.lock().calls.entry(key.clone()).or_default() += 1;
+
+    let req = MockRequest {
+        params,
+        query: query.to_string(),
+        headers: parts.headers,
+        body,
+    };
+    let resp = respond(&state, endpoint, &req).await;
+
+    *state.lock().responses.entry(key).or_default() += 1;
+    responded.notify_waiters();
+
+    Ok(resp)
+}
+
+async fn respond(state: &Mutex<State>, endpoint: &Endpoint, req: &MockRequest) -> Response<Body> {
+    let failure = state.lock().take_failure(endpoint.namespace, endpoint.name);
+
+    if let Some(failure) = failure {
+        if let Some(latency) = failure.latency {
+            tokio::time::sleep(latency).await;
+        }
+
+        if let Some(status) = failure.status {
+            return text(status, "injected failure");
+        }
+    }
+
+    let (resp, smtp, outbox) = {
+        let mut state = state.lock();
+        let resp = handler::handle(&mut state, endpoint.namespace, endpoint.name, req);
+
+        (resp, state.smtp, std::mem::take(&mut state.outbox))
+    };
+
+    // 응답하기 전에 보내서 `await_authcode`가 바로 받을 수 있게 함
+    if let Some(smtp) = smtp {
+        for mail in outbox {
+            if let Err(err) = mail::send(smtp, &mail).await {
+                log::warn!("mock: failed to send mail to {smtp}: {err}");
+            }
+        }
+    }
+
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "client")]
+    use super::fixtures::{book, login, EMAIL};
+    use super::*;
+
+    #[test]
+    fn test_route() {
+        let (x, params) = route("GET", "/books/1234/images/1.png", "").unwrap();
+        assert_eq!(x.name, "get_book_image");
+        assert_eq!(params["file_name"], "1.png");
+
+        assert_eq!(route("GET", "/books", "").unwrap().0.name, "get_books");
+        assert_eq!(
+            route("GET", "/books", "ids[0]=1&ids[1]=2").unwrap().0.name,
+            "get_books_by_ids"
+        );
+        assert_eq!(
+            route(
+                "GET",
+                "/books",
+                "tags[0][0]=female&tags[0][1]=loli&per-page=25&page=1"
+            )
+            .unwrap()
+            .0
+            .name,
+            "get_books_by_tags"
+        );
+
+        let (x, params) = route("GET", "/_/users/a%40b.com", "").unwrap();
+        assert_eq!(x.name, "get_user");
+        assert_eq!(params["user_id_or_email"], "a@b.com");
+    }
+
+    #[tokio::test]
+    async fn test_me() {
+        use chrono::Utc;
+
+        use crate::api::user::{self, payload};
+
+        let server = MockServer::start().await.unwrap();
+        let base_url = server.base_url();
+
+        let now = Utc::now();
+        let user_id = Uuid::from_u128(1);
+
+        server.seed_user(user::model::User {
+            id: user_id,
+            name: "madome".to_string(),
+            email: "madome@madome.app".to_string(),
+            role: 0,
+            created_at: now,
+            updated_at: now,
+        });
+        let token = server.issue_token_pair(user_id);
+
+        let me = user::get_me(&base_url, token.clone()).await.unwrap();
+        assert_eq!(me.id, user_id);
+
+        let like = payload::Like::Book { book_id: 1 };
+        user::create_like(&base_url, token.clone(), like.clone())
+            .await
+            .unwrap();
+        assert!(matches!(
+            user::create_like(&base_url, token.clone(), like.clone()).await,
+            Err(user::Error::CreateLike(
+                user::create_like::Error::AlreadyExistsLike
+            ))
+        ));
+
+        let likes = user::get_my_likes(&base_url, token.clone(), None, 25, 1, None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            likes[..],
+            [user::model::Like::Book { book_id: 1, .. }]
+        ));
+
+        // 받은 좋아요를 바꿔서 넘길 수 있음
+        user::delete_like(
+            &base_url,
+            token.clone(),
+            payload::Like::try_from(&likes[0]).unwrap(),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            user::delete_like(&base_url, token.clone(), like).await,
+            Err(user::Error::DeleteLike(
+                user::delete_like::Error::NotFoundLike
+            ))
+        ));
+
+        assert!(payload::Like::try_from(user::model::Like::Unknown).is_err());
+
+        let likes = vec![
+            payload::Like::Book { book_id: 1 },
+            payload::Like::Book { book_id: 1 },
+            payload::Like::BookTag {
+                tag: ("female", "loli").into(),
+            },
+        ];
+        let r = user::create_likes(&base_url, token.clone(), likes.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            r,
+            [
+                user::model::LikeResult::Created,
+                user::model::LikeResult::AlreadyExists,
+                user::model::LikeResult::Created,
+            ]
+        );
+
+        let (loli, glasses) = (
+            "female:loli".parse::<library::model::Tag>().unwrap(),
+            "male:glasses".parse::<library::model::Tag>().unwrap(),
+        );
+        let tags = vec![loli.clone(), glasses.clone()];
+        let state = user::is_liked(&base_url, token.clone(), vec![1, 2], tags)
+            .await
+            .unwrap();
+        assert!(state.is_book_liked(1));
+        assert!(!state.is_book_liked(2));
+        assert!(state.is_book_tag_liked(&loli));
+        assert!(!state.is_book_tag_liked(&glasses));
+
+        let r = user::delete_likes(&base_url, token.clone(), likes)
+            .await
+            .unwrap();
+        assert_eq!(
+            r,
+            [
+                user::model::LikeResult::Deleted,
+                user::model::LikeResult::NotFound,
+                user::model::LikeResult::Deleted,
+            ]
+        );
+
+        let history = payload::History::book(1, 1);
+        assert!(matches!(
+            user::create_or_update_history(&base_url, token, history).await,
+            Err(user::Error::CreateOrUpdateHistory(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_manage_user() {
+        use either::Either;
+
+        use crate::api::{user, BaseError};
+
+        let server = MockServer::start().await.unwrap();
+        let base_url = server.base_url();
+
+        user::create_user(&base_url, "", "madome", "madome@madome.app", None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            user::create_user(&base_url, "", "madome", "madome@madome.app", None).await,
+            Err(user::Error::CreateUser(user::create_user::Error::Conflict))
+        ));
+        user::create_user(&base_url, "", "admin", "admin@madome.app", Some(1))
+            .await
+            .unwrap();
+
+        let id = |email: &str| server.state.lock().find_user(email).unwrap().id;
+        let (user_id, admin_id) = (id("madome@madome.app"), id("admin@madome.app"));
+
+        let token = server.issue_token_pair(user_id);
+        let admin_token = server.issue_token_pair(admin_id);
+
+        let me = user::update_me(&base_url, token.clone(), Some("madome2".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(me.name, "madome2");
+        assert_eq!(me.email, "madome@madome.app");
+
+        // role이 낮은 유저는 다른 유저를 바꿀 수 없음
+        assert!(matches!(
+            user::update_user(
+                &base_url,
+                token.clone(),
+                Either::Left(admin_id),
+                None,
+                Some(0)
+            )
+            .await,
+            Err(user::Error::Base(BaseError::PermissionDenied))
+        ));
+
+        let x = user::update_user(
+            &base_url,
+            admin_token.clone(),
+            Either::Right("madome@madome.app".to_string()),
+            None,
+            Some(0),
+        )
+        .await
+        .unwrap();
+        assert_eq!(x.name, "madome2");
+
+        user::delete_user(&base_url, admin_token.clone(), Either::Left(user_id))
+            .await
+            .unwrap();
+        assert!(matches!(
+            user::delete_user(&base_url, admin_token.clone(), Either::Left(user_id)).await,
+            Err(user::Error::DeleteUser(
+                user::delete_user::Error::NotFoundUser
+            ))
+        ));
+
+        user::delete_me(&base_url, admin_token.clone())
+            .await
+            .unwrap();
+        assert!(matches!(
+            user::get_me(&base_url, admin_token).await,
+            Err(user::Error::Base(BaseError::Unauthorized))
+        ));
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_login_and_get_book() {
+        use chrono::Utc;
+
+        let server = MockServer::start().await.unwrap();
+        let client = server.client();
+
+        let now = Utc::now();
+        let user_id = Uuid::from_u128(1);
+
+        server.seed_user(user::model::User {
+            id: user_id,
+            name: "madome".to_string(),
+            email: EMAIL.to_string(),
+            role: 0,
+            created_at: now,
+            updated_at: now,
+        });
+        server.seed_book(book(1, 2));
+        server.seed_image(1, "1.png", vec![1, 2, 3]);
+
+        let r = client.library().get_book_by_id(1).await;
+        assert!(matches!(
+            r,
+            Err(library::Error::Base(crate::api::BaseError::Unauthorized))
+        ));
+
+        client.auth().create_authcode(EMAIL).await.unwrap();
+        let code = server.authcode(EMAIL).unwrap();
+        let r = client
+            .auth()
+            .create_token_pair(EMAIL, code)
+            .with_response()
+            .await
+            .unwrap();
+        assert_eq!(r.status, StatusCode::CREATED);
+        assert_eq!(r.set_cookies().count(), 2);
+
+        let r = client.auth().check_access_token().await.unwrap();
+        assert_eq!(r.user_id, user_id);
+
+        let book = client.library().get_book_by_id(1).await.unwrap();
+        assert_eq!(book.id, 1);
+
+        let image = client.library().get_book_image(1, "1.png").await.unwrap();
+        assert_eq!(image.as_ref(), &[1, 2, 3]);
+
+        server.fail(
+            "library",
+            "get_book_by_id",
+            Failure::status(StatusCode::SERVICE_UNAVAILABLE).times(1),
+        );
+        assert!(client.library().get_book_by_id(1).await.is_err());
+        assert!(client.library().get_book_by_id(1).await.is_ok());
+
+        client.auth().refresh_token_pair().await.unwrap();
+        assert!(client.library().get_book_by_id(1).await.is_ok());
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_get_books() {
+        use futures_util::TryStreamExt;
+
+        let server = MockServer::start().await.unwrap();
+        let client = login(&server).await;
+
+        server.seed_book(book(1, 2));
+
+        let book = client.library().get_book_by_id(1).await.unwrap();
+        let books = client.library().get_books().kind(book.kind).await.unwrap();
+        assert_eq!(books.len(), 1);
+
+        // 모르는 kind는 보내지 않고 에러
+        let r = client
+            .library()
+            .get_books()
+            .kind(library::model::BookKind::Unknown)
+            .await;
+        assert!(matches!(
+            r,
+            Err(library::Error::Base(crate::api::BaseError::UnknownKind(_)))
+        ));
+        assert_eq!(server.calls("library", "get_books"), 1);
+
+        let book = client.library().get_book_by_id_opt(2).await.unwrap();
+        assert!(book.is_none());
+
+        let books = client
+            .library()
+            .get_books()
+            .kind(library::payload::BookKind::Doujinshi)
+            .per_page(25)
+            .sort_by(library::payload::BookSortBy::IdDesc)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(books.len(), 1);
+
+        let books = client
+            .library()
+            .get_books()
+            .per_page(1)
+            .stream()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(books.len(), 1);
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_continue_reading() {
+        let server = MockServer::start().await.unwrap();
+        let client = login(&server).await;
+
+        server.seed_book(book(1, 2));
+
+        client
+            .user()
+            .create_or_update_history(user::payload::History::book(1, 1))
+            .await
+            .unwrap();
+        let reading = client.continue_reading(25, 1).await.unwrap();
+        assert_eq!(reading[0].page(), 1);
+        assert_eq!(reading[0].progress(), 0.5);
+
+        client
+            .user()
+            .create_or_update_history(user::payload::History::book(1, 2).completed(true))
+            .await
+            .unwrap();
+        assert!(client.continue_reading(25, 1).await.unwrap().is_empty());
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_resolve_likes() {
+        use chrono::{Duration, Utc};
+
+        let server = MockServer::start().await.unwrap();
+        let client = login(&server).await;
+        let user_id = Uuid::from_u128(1);
+
+        let now = Utc::now();
+        let artist = |i: u32| library::model::Tag::new("artist", format!("a{i}"));
+
+        // 10의 배수인 작품은 없음
+        for i in (1..=60).filter(|i| i % 10 != 0) {
+            server.seed_book(library::model::Book {
+                tags: vec![artist(i)],
+                ..book(i, 2)
+            });
+        }
+
+        // 최신순으로 작품 1..=60, 태그 a1..=a55
+        for i in 1..=60 {
+            server.seed_like(
+                user_id,
+                user::model::Like::Book {
+                    book_id: i,
+                    created_at: now - Duration::seconds(i as i64),
+                },
+            );
+        }
+        for i in 1..=55 {
+            server.seed_like(
+                user_id,
+                user::model::Like::BookTag {
+                    tag: artist(i),
+                    created_at: now - Duration::seconds(100 + i as i64),
+                },
+            );
+        }
+        server.seed_like(user_id, user::model::Like::Unknown);
+
+        let resolved = client.resolve_likes(10).await.unwrap();
+
+        let ids = resolved
+            .books
+            .iter()
+            .map(|(like, book)| {
+                assert!(
+                    matches!(like, user::model::Like::Book { book_id, .. } if *book_id == book.id)
+                );
+                book.id
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ids, (1..=60).filter(|i| i % 10 != 0).collect::<Vec<_>>());
+
+        assert_eq!(resolved.book_tags.len(), 55);
+        for ((like, books), i) in resolved.book_tags.iter().zip(1..) {
+            assert_eq!(like.tag(), Some(&artist(i)));
+
+            let ids = books.iter().map(|x| x.id).collect::<Vec<_>>();
+            if i % 10 == 0 {
+                assert!(ids.is_empty());
+            } else {
+                assert_eq!(ids, [i]);
+            }
+        }
+
+        assert_eq!(server.calls("library", "get_books_by_ids"), 2);
+        assert_eq!(server.calls("library", "get_books_by_tags"), 2);
+    }
+
+    #[cfg(feature = "client")]
+    #[tokio::test]
+    async fn test_devices() {
+        use crate::client::device::DeviceError;
+
+        let server = MockServer::start().await.unwrap();
+        let client = login(&server).await;
+
+        assert!(client.udid().is_none());
+        assert!(matches!(
+            client.register_device("fcm-token").await,
+            Err(DeviceError::NoUdid)
+        ));
+
+        let path = std::env::temp_dir().join(format!("madome-sdk-udid-{}", std::process::id()));
+        std::fs::remove_file(&path).ok();
+
+        let client = client.udid_path(&path).unwrap();
+        let udid = client.udid().unwrap();
+
+        // 다음 실행에도 같은 udid를 씀
+        assert_eq!(server.client().udid_path(&path).unwrap().udid(), Some(udid));
+        std::fs::remove_file(&path).unwrap();
+
+        client.register_device("fcm-token").await.unwrap();
+        client.register_device("fcm-token-2").await.unwrap();
+        let devices = client.user().get_devices().await.unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].udid, udid);
+        assert_eq!(devices[0].fcm_token, "fcm-token-2");
+
+        client.deregister_device().await.unwrap();
+        assert!(matches!(
+            client.deregister_device().await,
+            Err(DeviceError::User(user::Error::DeleteDevice(
+                user::delete_device::Error::NotFoundDevice
+            )))
+        ));
+    }
+}
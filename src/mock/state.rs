@@ -0,0 +1,114 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    time::Duration,
+};
+
+use bytes::Bytes;
+use http::StatusCode;
+use uuid::Uuid;
+
+use crate::api::{library, user};
+
+use super::mail::Mail;
+
+/// endpoint 별로 주입할 실패
+#[derive(Debug, Clone, Default)]
+pub struct Failure {
+    pub status: Option<StatusCode>,
+    pub latency: Option<Duration>,
+    /// `None`이면 해제하기 전까지 계속 실패함
+    pub times: Option<usize>,
+}
+
+impl Failure {
+    pub fn status(status: StatusCode) -> Self {
+        Self {
+            status: Some(status),
+            ..Default::default()
+        }
+    }
+
+    pub fn latency(latency: Duration) -> Self {
+        Self {
+            latency: Some(latency),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_latency(self, latency: Duration) -> Self {
+        Self {
+            latency: Some(latency),
+            ..self
+        }
+    }
+
+    pub fn times(self, times: usize) -> Self {
+        Self {
+            times: Some(times),
+            ..self
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct State {
+    pub users: Vec<user::model::User>,
+    pub likes: HashMap<Uuid, Vec<user::model::Like>>,
+    pub histories: HashMap<Uuid, Vec<user::model::History>>,
+    pub notifications: HashMap<Uuid, Vec<user::model::Notification>>,
+    pub devices: HashMap<Uuid, Vec<user::model::Device>>,
+
+    pub books: BTreeMap<u32, library::model::Book>,
+    pub images: HashMap<u32, Vec<(String, Bytes)>>,
+
+    /// (email, code)
+    pub authcodes: Vec<(String, String)>,
+    pub access_tokens: HashMap<String, Uuid>,
+    pub refresh_tokens: HashMap<String, Uuid>,
+
+    /// (namespace, endpoint name) -> failure
+    pub failures: HashMap<(String, String), Failure>,
+    /// (namespace, endpoint name) -> 요청 받은 횟수
+    pub calls: HashMap<(String, String), usize>,
+    /// (namespace, endpoint name) -> 응답한 횟수
+    pub responses: HashMap<(String, String), usize>,
+
+    /// 인증코드 메일을 보낼 SMTP 서버
+    pub smtp: Option<SocketAddr>,
+    /// 응답을 보낸 뒤 `smtp`로 보낼 메일
+    pub outbox: Vec<Mail>,
+
+    counter: u64,
+}
+
+impl State {
+    pub fn next(&mut self) -> u64 {
+        self.counter += 1;
+        self.counter
+    }
+
+    pub fn find_user(&self, user_id_or_email: &str) -> Option<&user::model::User> {
+        self.users
+            .iter()
+            .find(|x| x.id.to_string() == user_id_or_email || x.email == user_id_or_email)
+    }
+
+    /// 실패를 한번 소모함
+    pub fn take_failure(&mut self, namespace: &str, name: &str) -> Option<Failure> {
+        let key = (namespace.to_string(), name.to_string());
+
+        let failure = self.failures.get_mut(&key)?;
+        let r = failure.clone();
+
+        match failure.times.as_mut() {
+            Some(0 | 1) => {
+                self.failures.remove(&key);
+            }
+            Some(times) => *times -= 1,
+            None => {}
+        }
+
+        Some(r)
+    }
+}